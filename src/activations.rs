@@ -1,5 +1,6 @@
 //! Provides activation functions and their derivatives for neural networks.
 
+use crate::errors::{NeuroxError, NeuroxResult};
 use crate::tensor::Tensor;
 
 /// Applies the Rectified Linear Unit (ReLU) activation function element-wise.
@@ -16,6 +17,82 @@ pub fn relu_grad(x: &Tensor) -> Tensor {
     x.map(|v| if v > 0.0 { 1.0 } else { 0.0 })
 }
 
+/// Applies the Leaky ReLU activation function element-wise.
+///
+/// The function is defined as $f(x) = x$ if $x > 0$, and $\alpha x$ otherwise.
+pub fn leaky_relu(x: &Tensor, alpha: f32) -> Tensor {
+    x.map(|v| if v > 0.0 { v } else { alpha * v })
+}
+
+/// Computes the gradient of the Leaky ReLU function.
+///
+/// The derivative is $f'(x) = 1$ if $x > 0$, and $\alpha$ otherwise.
+pub fn leaky_relu_grad(x: &Tensor, alpha: f32) -> Tensor {
+    x.map(|v| if v > 0.0 { 1.0 } else { alpha })
+}
+
+/// Applies the Exponential Linear Unit (ELU) activation function element-wise.
+///
+/// The function is defined as $f(x) = x$ if $x > 0$, and $\alpha (e^x - 1)$ otherwise.
+/// It is continuous at zero and saturates smoothly to $-\alpha$ for very negative inputs.
+pub fn elu(x: &Tensor, alpha: f32) -> Tensor {
+    x.map(|v| if v > 0.0 { v } else { alpha * v.exp_m1() })
+}
+
+/// Computes the gradient of the ELU function.
+///
+/// The derivative is $f'(x) = 1$ if $x > 0$, and $\alpha e^x = f(x) + \alpha$ otherwise.
+pub fn elu_grad(x: &Tensor, alpha: f32) -> Tensor {
+    x.map(|v| if v > 0.0 { 1.0 } else { alpha * v.exp() })
+}
+
+/// Coefficient of the cubic term in the tanh approximation of GELU.
+const GELU_COEFF: f32 = 0.044715;
+
+/// Applies the Gaussian Error Linear Unit (GELU) activation function element-wise.
+///
+/// Uses the tanh approximation
+/// $f(x) = \frac{x}{2}\left(1 + \tanh\left(\sqrt{2/\pi}\,(x + 0.044715 x^3)\right)\right)$.
+pub fn gelu(x: &Tensor) -> Tensor {
+    x.map(gelu_scalar)
+}
+
+/// Computes the gradient of the (tanh-approximated) GELU function.
+///
+/// With $u = \sqrt{2/\pi}\,(x + 0.044715 x^3)$ and $t = \tanh(u)$, the derivative is
+/// $f'(x) = \frac{1}{2}(1 + t) + \frac{x}{2}(1 - t^2)\sqrt{2/\pi}\,(1 + 3 \cdot 0.044715 x^2)$.
+pub fn gelu_grad(x: &Tensor) -> Tensor {
+    let c = (2.0 / std::f32::consts::PI).sqrt();
+    x.map(|v| {
+        let t = (c * (v + GELU_COEFF * v * v * v)).tanh();
+        0.5 * (1.0 + t) + 0.5 * v * (1.0 - t * t) * c * (1.0 + 3.0 * GELU_COEFF * v * v)
+    })
+}
+
+/// GELU of a single value; shared with `Dense`'s fused forward pass.
+pub(crate) fn gelu_scalar(v: f32) -> f32 {
+    let c = (2.0 / std::f32::consts::PI).sqrt();
+    0.5 * v * (1.0 + (c * (v + GELU_COEFF * v * v * v)).tanh())
+}
+
+/// Applies the Softplus activation function element-wise.
+///
+/// The function is defined as $f(x) = \ln(1 + e^x)$, evaluated in the stable form
+/// $\max(x, 0) + \ln(1 + e^{-|x|})$ so that large inputs do not overflow `exp`.
+pub fn softplus(x: &Tensor) -> Tensor {
+    x.map(softplus_scalar)
+}
+
+/// Computes the gradient of the Softplus function, which is $\sigma(x)$.
+pub fn softplus_grad(x: &Tensor) -> Tensor {
+    sigmoid(x)
+}
+
+/// Softplus of a single value; shared with `Dense`'s fused forward pass.
+pub(crate) fn softplus_scalar(v: f32) -> f32 {
+    v.max(0.0) + (-v.abs()).exp().ln_1p()
+}
+
 /// Applies the Sigmoid activation function element-wise.
 ///
 /// The function is defined as $\sigma(x) = \frac{1}{1 + e^{-x}}$.
@@ -46,11 +123,50 @@ pub fn tanh_grad_from_out(tanh_out: &Tensor) -> Tensor {
     tanh_out.map(|t| 1.0 - t * t)
 }
 
+/// Applies ReLU to `x` in place, avoiding the allocation of `relu`.
+///
+/// The pre-activation values are lost, so a later backward pass must work from the
+/// output instead: `relu_grad` of the output equals `relu_grad` of the input, since an
+/// output is positive exactly where its input was.
+pub fn relu_inplace(x: &mut Tensor) {
+    x.data
+        .iter_mut()
+        .for_each(|v| *v = if *v > 0.0 { *v } else { 0.0 });
+}
+
+/// Applies Leaky ReLU to `x` in place, avoiding the allocation of `leaky_relu`.
+///
+/// For a positive `alpha` the sign is preserved, so `leaky_relu_grad` of the output
+/// still gives the gradient after the pre-activation is overwritten.
+pub fn leaky_relu_inplace(x: &mut Tensor, alpha: f32) {
+    x.data
+        .iter_mut()
+        .for_each(|v| *v = if *v > 0.0 { *v } else { alpha * *v });
+}
+
+/// Applies Sigmoid to `x` in place, avoiding the allocation of `sigmoid`.
+///
+/// The gradient only needs the output, via `sigmoid_grad_from_out`.
+pub fn sigmoid_inplace(x: &mut Tensor) {
+    x.data
+        .iter_mut()
+        .for_each(|v| *v = 1.0 / (1.0 + (-*v).exp()));
+}
+
+/// Applies tanh to `x` in place, avoiding the allocation of `tanh`.
+///
+/// The gradient only needs the output, via `tanh_grad_from_out`.
+pub fn tanh_inplace(x: &mut Tensor) {
+    x.data.iter_mut().for_each(|v| *v = v.tanh());
+}
+
 /// Applies the Softmax function to each row of the input tensor.
 ///
 /// This implementation is numerically stable, preventing overflow by subtracting
 /// the maximum value in each row before exponentiation. The function is defined as:
 /// $$\text{Softmax}(x_i) = \frac{e^{x_i}}{\sum_{j} e^{x_j}}$$
+///
+/// This is the `axis = 1` case of `softmax_axis`.
 pub fn softmax(x: &Tensor) -> Tensor {
     let mut out = x.clone();
     for i in 0..x.rows {
@@ -75,3 +191,64 @@ pub fn softmax(x: &Tensor) -> Tensor {
     }
     out
 }
+
+/// Backpropagates `grad_out` through a row-wise Softmax, given its output.
+///
+/// Softmax mixes every entry of a row, so instead of an element-wise product this is the
+/// Jacobian-vector product `s_j * (g_j - sum_k g_k s_k)` for each row, computed without
+/// forming the `(cols, cols)` Jacobian.
+///
+/// # Panics
+///
+/// Panics if `softmax_out` and `grad_out` differ in shape.
+pub fn softmax_grad_jvp(softmax_out: &Tensor, grad_out: &Tensor) -> Tensor {
+    assert_eq!(softmax_out.shape(), grad_out.shape());
+    let cols = softmax_out.cols;
+    let mut out = grad_out.zeros_like();
+    for i in 0..softmax_out.rows {
+        let s = &softmax_out.data[i * cols..(i + 1) * cols];
+        let g = &grad_out.data[i * cols..(i + 1) * cols];
+        let dot: f32 = s.iter().zip(g).map(|(s, g)| s * g).sum();
+        for j in 0..cols {
+            out.data[i * cols + j] = s[j] * (g[j] - dot);
+        }
+    }
+    out
+}
+
+/// Applies the logarithm of the Softmax function to each row of the input tensor.
+///
+/// Computes `(x - max) - ln(sum(exp(x - max)))` per row, so no probability is ever
+/// formed: log-probabilities far below `ln(f32::MIN_POSITIVE)` stay finite where
+/// `softmax` followed by `ln` would underflow to `-inf`.
+pub fn log_softmax(x: &Tensor) -> Tensor {
+    let mut out = x.clone();
+    if x.cols == 0 {
+        return out;
+    }
+    for row in out.data.chunks_mut(x.cols) {
+        let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let lse = row.iter().map(|v| (v - max).exp()).sum::<f32>().ln();
+        row.iter_mut().for_each(|v| *v = (*v - max) - lse);
+    }
+    out
+}
+
+/// Applies the Softmax function along the given axis.
+///
+/// `axis = 1` normalizes each row (same as `softmax`) and `axis = 0` normalizes each
+/// column. Both use the same max-subtraction for numerical stability.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `axis` is not `0` or `1`.
+pub fn softmax_axis(x: &Tensor, axis: usize) -> NeuroxResult<Tensor> {
+    match axis {
+        0 => Ok(softmax(&x.transpose()).transpose()),
+        1 => Ok(softmax(x)),
+        _ => Err(NeuroxError::InvalidArgument(format!(
+            "softmax axis must be 0 or 1, got {}",
+            axis
+        ))),
+    }
+}
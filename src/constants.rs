@@ -0,0 +1,18 @@
+//! Default numerical tolerances used across the crate.
+//!
+//! Each default can be overridden where it is used: `Adam::eps`, `AdaGrad::eps` and
+//! `GroupNorm::eps` are public fields, and `loss::cross_entropy_loss_with_eps` takes the
+//! clamp explicitly.
+
+/// Lower clamp applied to probabilities before taking their logarithm in the
+/// cross-entropy losses.
+pub const LOG_EPS: f32 = 1e-7;
+
+/// Default `Adam::eps`, added to the root of the second-moment estimate.
+pub const ADAM_EPS: f32 = 1e-8;
+
+/// Default `AdaGrad::eps`, added to the root of the accumulated squared gradients.
+pub const ADAGRAD_EPS: f32 = 1e-10;
+
+/// Default `GroupNorm::eps`, added to the variance before the square root.
+pub const NORM_EPS: f32 = 1e-5;
@@ -2,6 +2,10 @@
 
 use crate::errors::{NeuroxError, NeuroxResult};
 use crate::tensor::Tensor;
+use crate::utils;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
@@ -29,21 +33,10 @@ pub fn tensor_from_csv(path: &str) -> NeuroxResult<Tensor> {
             .collect();
         rows.push(vals);
     }
-    let r = rows.len();
-    if r == 0 {
+    if rows.is_empty() {
         return Err(NeuroxError::InvalidArgument("empty csv".into()));
     }
-    let c = rows[0].len();
-    let mut data = Vec::with_capacity(r * c);
-    for row in rows {
-        if row.len() != c {
-            return Err(NeuroxError::InvalidArgument(
-                "csv has non-uniform row length".into(),
-            ));
-        }
-        data.extend_from_slice(&row);
-    }
-    Ok(Tensor::from_data(data, r, c))
+    Tensor::from_rows(rows)
 }
 
 /// Splits a tensor's rows into two tensors for training and testing.
@@ -61,20 +54,541 @@ pub fn train_test_split(t: &Tensor, ratio: f32) -> NeuroxResult<(Tensor, Tensor)
     );
     let n = t.rows;
     let train_n = ((n as f32) * ratio).round() as usize;
-    let train = slice_rows(t, 0, train_n)?;
-    let test = slice_rows(t, train_n, n)?;
-    Ok((train, test))
+    t.split_at_row(train_n)
+}
+
+/// Computes stratified k-fold cross-validation splits as row indices.
+///
+/// Rows are grouped by the argmax of `y_onehot`, each class is shuffled with `seed`, and
+/// its rows are dealt round-robin across the folds, continuing from where the previous
+/// class stopped. Every class is therefore split as evenly as possible (fold counts differ
+/// by at most one) and fold sizes stay balanced. Returns one `(train_idx, val_idx)` pair
+/// per fold, both sorted; every row appears in exactly one validation fold. The indices
+/// can be applied to any array aligned with `y_onehot`.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `k < 2` or `k` exceeds the number of rows.
+pub fn stratified_k_fold_indices(
+    y_onehot: &Tensor,
+    k: usize,
+    seed: u64,
+) -> NeuroxResult<Vec<(Vec<usize>, Vec<usize>)>> {
+    if k < 2 || k > y_onehot.rows {
+        return Err(NeuroxError::InvalidArgument(format!(
+            "k must be between 2 and {} rows, got {}",
+            y_onehot.rows, k
+        )));
+    }
+    let mut by_class = vec![Vec::new(); y_onehot.cols];
+    for (i, c) in y_onehot.argmax_rows().into_iter().enumerate() {
+        by_class[c].push(i);
+    }
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut fold_of = vec![0; y_onehot.rows];
+    let mut next = 0;
+    for rows in by_class.iter_mut() {
+        rows.shuffle(&mut rng);
+        for &i in rows.iter() {
+            fold_of[i] = next % k;
+            next += 1;
+        }
+    }
+    Ok((0..k)
+        .map(|fold| (0..y_onehot.rows).partition(|&i| fold_of[i] != fold))
+        .collect())
+}
+
+/// Generates a synthetic classification dataset of Gaussian clusters.
+///
+/// Each class gets a center drawn uniformly from `[-3, 3]^n_features`, and samples are
+/// that center plus unit-variance Gaussian noise, so the classes are mostly but not
+/// perfectly separable. Samples cycle through the classes in order, giving balanced
+/// classes. Returns `(x, y)` with `x` of shape `(n_samples, n_features)` and one-hot
+/// `y` of shape `(n_samples, n_classes)`. The same `seed` always yields the same data.
+///
+/// # Panics
+///
+/// Panics if `n_classes` is zero.
+pub fn make_classification(
+    n_samples: usize,
+    n_features: usize,
+    n_classes: usize,
+    seed: u64,
+) -> (Tensor, Tensor) {
+    assert!(n_classes > 0, "n_classes must be positive");
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let centers: Vec<f32> = (0..n_classes * n_features)
+        .map(|_| rng.random_range(-3.0..3.0))
+        .collect();
+    let mut x = Tensor::zeros(n_samples, n_features);
+    let mut y = Tensor::zeros(n_samples, n_classes);
+    for i in 0..n_samples {
+        let class = i % n_classes;
+        for j in 0..n_features {
+            x.set(
+                i,
+                j,
+                centers[class * n_features + j] + utils::sample_normal(&mut rng),
+            );
+        }
+        y.set(i, class, 1.0);
+    }
+    (x, y)
+}
+
+/// Generates a synthetic linear regression dataset.
+///
+/// Features are standard normal and the target is a linear combination of them, with
+/// coefficients drawn uniformly from `[-1, 1]`, plus Gaussian noise of standard deviation
+/// `noise`. Returns `(x, y)` with `x` of shape `(n_samples, n_features)` and `y` of shape
+/// `(n_samples, 1)`. The same `seed` always yields the same data.
+pub fn make_regression(
+    n_samples: usize,
+    n_features: usize,
+    noise: f32,
+    seed: u64,
+) -> (Tensor, Tensor) {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let coef: Vec<f32> = (0..n_features)
+        .map(|_| rng.random_range(-1.0..1.0))
+        .collect();
+    let mut x = Tensor::zeros(n_samples, n_features);
+    let mut y = Tensor::zeros(n_samples, 1);
+    for i in 0..n_samples {
+        let mut target = 0.0;
+        for (j, c) in coef.iter().enumerate() {
+            let v = utils::sample_normal(&mut rng);
+            x.set(i, j, v);
+            target += c * v;
+        }
+        y.set(i, 0, target + noise * utils::sample_normal(&mut rng));
+    }
+    (x, y)
+}
+
+/// Accumulates per-column mean and standard deviation over a stream of batches.
+///
+/// Uses Welford's online algorithm, so memory is constant in the number of rows and the
+/// variance does not suffer from the cancellation of the naive `E[x²] - E[x]²` formula.
+#[derive(Clone, Debug, Default)]
+pub struct RunningStats {
+    count: usize,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl RunningStats {
+    /// Creates an empty accumulator. The column count is fixed by the first batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of rows seen so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Folds every row of `batch` into the running statistics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` has a different number of columns than earlier batches.
+    pub fn update(&mut self, batch: &Tensor) {
+        if self.count == 0 {
+            self.mean = vec![0.0; batch.cols];
+            self.m2 = vec![0.0; batch.cols];
+        }
+        assert_eq!(
+            batch.cols,
+            self.mean.len(),
+            "batch column count must match previous batches"
+        );
+        for i in 0..batch.rows {
+            self.count += 1;
+            let n = self.count as f64;
+            for j in 0..batch.cols {
+                let x = batch.get(i, j) as f64;
+                let delta = x - self.mean[j];
+                self.mean[j] += delta / n;
+                self.m2[j] += delta * (x - self.mean[j]);
+            }
+        }
+    }
+
+    /// Returns `(mean, std)` as `(1, cols)` tensors, using the population standard deviation.
+    ///
+    /// Both are zero if no rows have been seen.
+    pub fn finalize(&self) -> (Tensor, Tensor) {
+        let cols = self.mean.len();
+        let mean = self.mean.iter().map(|&m| m as f32).collect();
+        let std = self
+            .m2
+            .iter()
+            .map(|&m2| {
+                if self.count == 0 {
+                    0.0
+                } else {
+                    (m2 / self.count as f64).sqrt() as f32
+                }
+            })
+            .collect();
+        (
+            Tensor::from_data(mean, 1, cols),
+            Tensor::from_data(std, 1, cols),
+        )
+    }
+}
+
+/// Derives inverse-frequency class weights from one-hot (or soft) labels.
+///
+/// Class `c` gets `total / (num_classes * count_c)`, where `count_c` is the column sum of
+/// `y_onehot`, so rare classes weigh more and a balanced set gives all ones. A class with
+/// no samples gets weight `0.0`, since it cannot contribute to the loss anyway. The result
+/// is a `(1, num_classes)` tensor for `loss::weighted_cross_entropy_loss`.
+pub fn class_weights(y_onehot: &Tensor) -> Tensor {
+    let classes = y_onehot.cols;
+    let mut counts = vec![0.0f32; classes];
+    for i in 0..y_onehot.rows {
+        for (j, c) in counts.iter_mut().enumerate() {
+            *c += y_onehot.get(i, j);
+        }
+    }
+    let total: f32 = counts.iter().sum();
+    let data = counts
+        .iter()
+        .map(|&c| {
+            if c > 0.0 {
+                total / (classes as f32 * c)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    Tensor::from_data(data, 1, classes)
+}
+
+/// A minibatch drawn from a `ReplayBuffer`.
+#[derive(Clone, Debug)]
+pub struct ReplayBatch {
+    /// States, one per row: `(batch, state_dim)`.
+    pub states: Tensor,
+    /// Action taken in each state.
+    pub actions: Vec<usize>,
+    /// Rewards as a `(batch, 1)` column.
+    pub rewards: Tensor,
+    /// Resulting states: `(batch, state_dim)`.
+    pub next_states: Tensor,
+}
+
+/// Fixed-capacity circular store of `(state, action, reward, next_state)` transitions.
+///
+/// Once full, each `push` overwrites the oldest transition. States are `(1, state_dim)`
+/// row tensors; the dimension is fixed by the first push.
+#[derive(Clone, Debug)]
+pub struct ReplayBuffer {
+    capacity: usize,
+    state_dim: usize,
+    states: Vec<f32>,
+    actions: Vec<usize>,
+    rewards: Vec<f32>,
+    next_states: Vec<f32>,
+    // Slot the next push writes to once the buffer is full.
+    head: usize,
+}
+
+impl ReplayBuffer {
+    /// Creates an empty buffer holding at most `capacity` transitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Replay buffer capacity must be positive");
+        Self {
+            capacity,
+            state_dim: 0,
+            states: Vec::new(),
+            actions: Vec::new(),
+            rewards: Vec::new(),
+            next_states: Vec::new(),
+            head: 0,
+        }
+    }
+
+    /// Returns the number of stored transitions.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns `true` if no transitions are stored.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the maximum number of stored transitions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Stores a transition, evicting the oldest one if the buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `state` or `next_state` is not a single row
+    /// of the buffer's state dimension.
+    pub fn push(
+        &mut self,
+        state: &Tensor,
+        action: usize,
+        reward: f32,
+        next_state: &Tensor,
+    ) -> NeuroxResult<()> {
+        if self.is_empty() {
+            self.state_dim = state.cols;
+        }
+        for t in [state, next_state] {
+            if t.shape() != (1, self.state_dim) {
+                return Err(NeuroxError::ShapeMismatch(format!(
+                    "states must be (1, {}), got {:?}",
+                    self.state_dim,
+                    t.shape()
+                )));
+            }
+        }
+
+        if self.len() < self.capacity {
+            self.states.extend_from_slice(&state.data);
+            self.actions.push(action);
+            self.rewards.push(reward);
+            self.next_states.extend_from_slice(&next_state.data);
+        } else {
+            let d = self.state_dim;
+            let slot = self.head;
+            self.states[slot * d..(slot + 1) * d].copy_from_slice(&state.data);
+            self.actions[slot] = action;
+            self.rewards[slot] = reward;
+            self.next_states[slot * d..(slot + 1) * d].copy_from_slice(&next_state.data);
+            self.head = (slot + 1) % self.capacity;
+        }
+        Ok(())
+    }
+
+    /// Draws `batch_size` distinct transitions uniformly at random.
+    ///
+    /// Sampling uses its own RNG seeded from `seed`, so the same seed and buffer contents
+    /// always give the same batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `batch_size` exceeds `len()`.
+    pub fn sample(&self, batch_size: usize, seed: u64) -> NeuroxResult<ReplayBatch> {
+        if batch_size > self.len() {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "cannot sample {} transitions from a buffer of {}",
+                batch_size,
+                self.len()
+            )));
+        }
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let picks = rand::seq::index::sample(&mut rng, self.len(), batch_size);
+
+        let d = self.state_dim;
+        let mut states = Vec::with_capacity(batch_size * d);
+        let mut actions = Vec::with_capacity(batch_size);
+        let mut rewards = Vec::with_capacity(batch_size);
+        let mut next_states = Vec::with_capacity(batch_size * d);
+        for i in picks {
+            states.extend_from_slice(&self.states[i * d..(i + 1) * d]);
+            actions.push(self.actions[i]);
+            rewards.push(self.rewards[i]);
+            next_states.extend_from_slice(&self.next_states[i * d..(i + 1) * d]);
+        }
+        Ok(ReplayBatch {
+            states: Tensor::from_data(states, batch_size, d),
+            actions,
+            rewards: Tensor::from_data(rewards, batch_size, 1),
+            next_states: Tensor::from_data(next_states, batch_size, d),
+        })
+    }
 }
 
-/// Helper to extract a horizontal slice of a tensor.
-fn slice_rows(t: &Tensor, start: usize, end: usize) -> NeuroxResult<Tensor> {
-    assert!(start <= end && end <= t.rows);
-    let cols = t.cols;
-    let mut out = Tensor::zeros(end - start, cols);
-    for i in 0..(end - start) {
-        for j in 0..cols {
-            out.set(i, j, t.get(start + i, j));
+/// Standardizes columns to zero mean and unit variance using statistics learned once.
+///
+/// `fit` learns per-column mean and population standard deviation from training data;
+/// `transform` then applies `(x - mean) / std` to any tensor with the same columns, e.g.
+/// a test split. Constant columns are only centered, so they map to zero rather than NaN.
+#[derive(Clone, Debug, Default)]
+pub struct StandardScaler {
+    mean: Option<Tensor>,
+    std: Option<Tensor>,
+}
+
+impl StandardScaler {
+    /// Creates an unfitted scaler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Learns the per-column mean and standard deviation of `t`, replacing earlier ones.
+    pub fn fit(&mut self, t: &Tensor) {
+        let mut stats = RunningStats::new();
+        stats.update(t);
+        let (mean, std) = stats.finalize();
+        self.mean = Some(mean);
+        self.std = Some(std.map(|s| if s > 0.0 { s } else { 1.0 }));
+    }
+
+    /// Returns the learned `(mean, std)` as `(1, cols)` tensors, if fitted.
+    pub fn stats(&self) -> Option<(&Tensor, &Tensor)> {
+        Some((self.mean.as_ref()?, self.std.as_ref()?))
+    }
+
+    /// Standardizes `t` with the learned statistics.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if the scaler has not been fitted, or
+    /// `NeuroxError::ShapeMismatch` if `t` has a different column count than the fit data.
+    pub fn transform(&self, t: &Tensor) -> NeuroxResult<Tensor> {
+        let (mean, std) = self.stats().ok_or_else(|| {
+            NeuroxError::InvalidArgument("StandardScaler must be fitted before transform".into())
+        })?;
+        let centered = t.sub_row_broadcast(mean)?;
+        crate::ops::div(&centered, std)
+    }
+
+    /// Fits on `t` and returns it standardized.
+    ///
+    /// # Errors
+    ///
+    /// Same as `transform`.
+    pub fn fit_transform(&mut self, t: &Tensor) -> NeuroxResult<Tensor> {
+        self.fit(t);
+        self.transform(t)
+    }
+}
+
+/// Computes the top principal directions of `t` as a `(features, n_components)` matrix.
+///
+/// The columns are unit-length eigenvectors of the sample covariance matrix, ordered by
+/// decreasing variance, found by Jacobi eigendecomposition in `f64`. Each column's sign
+/// is chosen so its largest-magnitude entry is positive, making the result deterministic.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `t` has fewer than two rows or
+/// `n_components` is zero or exceeds the feature count.
+pub fn pca_fit(t: &Tensor, n_components: usize) -> NeuroxResult<Tensor> {
+    let f = t.cols;
+    if t.rows < 2 || n_components == 0 || n_components > f {
+        return Err(NeuroxError::InvalidArgument(format!(
+            "pca_fit needs at least 2 rows and 1..={} components, got {} rows and {}",
+            f, t.rows, n_components
+        )));
+    }
+    let n = t.rows as f64;
+    let mean: Vec<f64> = (0..f)
+        .map(|j| (0..t.rows).map(|i| t.get(i, j) as f64).sum::<f64>() / n)
+        .collect();
+    let mut cov = vec![0.0f64; f * f];
+    for i in 0..t.rows {
+        for p in 0..f {
+            let dp = t.get(i, p) as f64 - mean[p];
+            for q in p..f {
+                cov[p * f + q] += dp * (t.get(i, q) as f64 - mean[q]);
+            }
+        }
+    }
+    for p in 0..f {
+        for q in p..f {
+            cov[p * f + q] /= n - 1.0;
+            cov[q * f + p] = cov[p * f + q];
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(cov, f);
+    let mut order: Vec<usize> = (0..f).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+
+    let mut out = Tensor::zeros(f, n_components);
+    for (c, &k) in order.iter().take(n_components).enumerate() {
+        let column: Vec<f64> = (0..f).map(|r| vectors[r * f + k]).collect();
+        let pivot = column
+            .iter()
+            .copied()
+            .fold(0.0f64, |m, v| if v.abs() > m.abs() { v } else { m });
+        let sign = if pivot < 0.0 { -1.0 } else { 1.0 };
+        for (r, v) in column.iter().enumerate() {
+            out.set(r, c, (sign * v) as f32);
         }
     }
     Ok(out)
 }
+
+/// Projects `t` onto principal directions from `pca_fit`, giving `(rows, n_components)`.
+///
+/// No centering is applied; subtract the training mean first (e.g. with
+/// `Tensor::sub_row_broadcast`) to get the usual centered PCA scores.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `t.cols` is not `components.rows`.
+pub fn pca_transform(t: &Tensor, components: &Tensor) -> NeuroxResult<Tensor> {
+    if t.cols != components.rows {
+        return Err(NeuroxError::ShapeMismatch(format!(
+            "data has {} features but components expect {}",
+            t.cols, components.rows
+        )));
+    }
+    crate::ops::matmul(t, components)
+}
+
+/// Eigendecomposition of a symmetric `n x n` matrix (row-major) by cyclic Jacobi rotations.
+///
+/// Returns `(eigenvalues, eigenvectors)`, with eigenvector `k` stored in column `k` of
+/// the row-major `n x n` result.
+fn symmetric_eigen(mut a: Vec<f64>, n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut v = vec![0.0f64; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+    for _sweep in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|p| (p + 1..n).map(move |q| (p, q)))
+            .map(|(p, q)| a[p * n + q].powi(2))
+            .sum();
+        if off < 1e-24 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    let values = (0..n).map(|i| a[i * n + i]).collect();
+    (values, v)
+}
@@ -1,7 +1,24 @@
 //! Defines the layers of a neural network, such as the `Dense` layer.
 
-use crate::errors::NeuroxResult;
-use crate::{activations, ops, tensor::Tensor};
+use crate::errors::{NeuroxError, NeuroxResult};
+use crate::{activations, constants, ops, tensor::Tensor, utils};
+use rand::Rng;
+
+/// Common interface of layers that can be chained in a forward/backward pass.
+pub trait Layer {
+    /// Runs the forward pass, caching whatever `backward` needs.
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor>;
+
+    /// Backpropagates `grad_out`, storing parameter gradients and returning the gradient
+    /// with respect to the layer's input.
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor>;
+
+    /// Takes a plain SGD step on the stored gradients. No-op for parameter-free layers.
+    fn apply_gradients(&mut self, lr: f32);
+
+    /// Returns the number of trainable parameters.
+    fn num_params(&self) -> usize;
+}
 
 /// A fully-connected (dense) neural network layer.
 ///
@@ -24,17 +41,222 @@ pub struct Dense {
     pub grad_w: Option<Tensor>,
     /// Gradient of the loss with respect to the biases, computed during the backward pass.
     pub grad_b: Option<Tensor>,
+    /// Pruning mask over `w` (row-major); weights marked `false` are held at zero.
+    pub mask: Option<Vec<bool>>,
+    /// Optional dropout applied to the activated output.
+    pub dropout: Option<Dropout>,
+    /// When set, `forward` does not keep the pre-activation and `backward` recomputes it
+    /// from the cached input, trading compute for memory. See
+    /// `Model::set_checkpoint_memory_budget`.
+    pub recompute_preact: bool,
 }
 
 /// An enumeration of supported activation functions for a layer.
 #[derive(Clone, Copy, Debug)]
 pub enum Activation {
     ReLU,
+    /// Leaky ReLU with the given negative slope `alpha`.
+    LeakyReLU(f32),
+    /// Exponential Linear Unit with the given saturation value `alpha`.
+    ELU(f32),
+    /// Gaussian Error Linear Unit, in its tanh approximation.
+    GELU,
+    /// Softplus `ln(1 + e^x)`, a smooth approximation of ReLU.
+    Softplus,
+    /// Row-wise Softmax, so the layer outputs probabilities. `Model::train` already
+    /// applies Softmax to the final outputs, so pair this with `Model::train_with_loss`
+    /// and a loss on probabilities instead.
+    Softmax,
     Sigmoid,
     Tanh,
     None,
 }
 
+/// Weight initialization schemes for `Dense::with_init`.
+///
+/// `fan_in` and `fan_out` are the layer's input and output feature counts.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Init {
+    /// Weights and biases from `U(-1, 1)`. This is what `Dense::new` uses.
+    #[default]
+    Uniform,
+    /// Glorot/Xavier uniform: `U(-a, a)` with `a = sqrt(6 / (fan_in + fan_out))`.
+    Xavier,
+    /// He normal: `N(0, 2 / fan_in)`, suited to ReLU networks.
+    HeNormal,
+    /// He uniform: `U(-a, a)` with `a = sqrt(6 / fan_in)`, suited to ReLU networks.
+    HeUniform,
+    /// LeCun normal: `N(0, 1 / fan_in)`, suited to SELU networks.
+    LecunNormal,
+}
+
+impl Init {
+    /// Samples a `(fan_in, fan_out)` weight matrix.
+    pub fn weights(self, fan_in: usize, fan_out: usize) -> Tensor {
+        let fi = fan_in as f32;
+        match self {
+            Init::Uniform => Tensor::random(fan_in, fan_out),
+            Init::Xavier => {
+                let a = (6.0 / (fi + fan_out as f32)).sqrt();
+                Tensor::random_uniform(fan_in, fan_out, -a, a)
+            }
+            Init::HeNormal => Tensor::random_normal(fan_in, fan_out, 0.0, (2.0 / fi).sqrt()),
+            Init::HeUniform => {
+                let a = (6.0 / fi).sqrt();
+                Tensor::random_uniform(fan_in, fan_out, -a, a)
+            }
+            Init::LecunNormal => Tensor::random_normal(fan_in, fan_out, 0.0, (1.0 / fi).sqrt()),
+        }
+    }
+
+    /// Samples a `(1, fan_out)` bias vector: uniform for `Init::Uniform`, zeros otherwise.
+    pub fn biases(self, fan_out: usize) -> Tensor {
+        match self {
+            Init::Uniform => Tensor::random(1, fan_out),
+            _ => Tensor::zeros(1, fan_out),
+        }
+    }
+}
+
+/// Declarative description of one layer, used by `Model::from_configs`.
+#[derive(Clone, Copy, Debug)]
+pub struct LayerConfig {
+    /// Number of output features of the layer.
+    pub size: usize,
+    /// Activation applied after the linear transformation.
+    pub activation: Activation,
+    /// Dropout rate applied to the activated output, if any.
+    pub dropout: Option<f32>,
+}
+
+/// Inverted dropout: during training, zeros each element with probability `rate` and
+/// scales the survivors by `1 / (1 - rate)`, so inference needs no rescaling.
+///
+/// With `per_column` set (see `Dropout::columns`), whole feature columns are dropped
+/// instead, using one mask shared by every row of the batch; this is the tabular analog
+/// of spatial dropout.
+#[derive(Clone, Debug)]
+pub struct Dropout {
+    /// Probability of dropping each element (or each column when `per_column` is set).
+    pub rate: f32,
+    /// Dropout is only applied while `training` is `true`; otherwise it is the identity.
+    pub training: bool,
+    /// Drop whole columns rather than individual elements.
+    pub per_column: bool,
+    // Scaled keep-mask from the last training forward pass, reused by backward.
+    mask: Option<Tensor>,
+}
+
+impl Dropout {
+    /// Creates a dropout stage in training mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not in `[0.0, 1.0)`.
+    pub fn new(rate: f32) -> Self {
+        assert!(
+            (0.0..1.0).contains(&rate),
+            "Dropout rate must be in [0.0, 1.0)"
+        );
+        Self {
+            rate,
+            training: true,
+            per_column: false,
+            mask: None,
+        }
+    }
+
+    /// Creates a dropout stage in training mode that drops whole feature columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not in `[0.0, 1.0)`.
+    pub fn columns(rate: f32) -> Self {
+        Self {
+            per_column: true,
+            ..Self::new(rate)
+        }
+    }
+
+    /// Applies dropout to `input`, sampling a fresh mask from the crate RNG when training.
+    pub fn forward(&mut self, input: &Tensor) -> Tensor {
+        if !self.training || self.rate == 0.0 {
+            self.mask = None;
+            return input.clone();
+        }
+        let scale = 1.0 / (1.0 - self.rate);
+        let mask = utils::with_rng(|rng| {
+            let mut draw = |_| {
+                if rng.random::<f32>() < self.rate {
+                    0.0
+                } else {
+                    scale
+                }
+            };
+            if self.per_column {
+                let cols = Tensor::new(1, input.cols).map(&mut draw);
+                Tensor::from_data(cols.data.repeat(input.rows), input.rows, input.cols)
+            } else {
+                input.map(draw)
+            }
+        });
+        let out = input
+            .data
+            .iter()
+            .zip(&mask.data)
+            .map(|(x, m)| x * m)
+            .collect();
+        self.mask = Some(mask);
+        Tensor::from_data(out, input.rows, input.cols)
+    }
+
+    /// Routes `grad_out` through the mask used in the last forward pass.
+    pub fn backward(&self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        match &self.mask {
+            Some(mask) => ops::mul_elementwise(grad_out, mask),
+            None => Ok(grad_out.clone()),
+        }
+    }
+}
+
+impl Activation {
+    /// Applies the activation to a single pre-activation value.
+    ///
+    /// Matches the element-wise functions in `activations` exactly.
+    fn apply(self, v: f32) -> f32 {
+        match self {
+            Activation::ReLU => {
+                if v > 0.0 {
+                    v
+                } else {
+                    0.0
+                }
+            }
+            Activation::LeakyReLU(alpha) => {
+                if v > 0.0 {
+                    v
+                } else {
+                    alpha * v
+                }
+            }
+            Activation::ELU(alpha) => {
+                if v > 0.0 {
+                    v
+                } else {
+                    alpha * v.exp_m1()
+                }
+            }
+            Activation::GELU => activations::gelu_scalar(v),
+            Activation::Softplus => activations::softplus_scalar(v),
+            // Row-wise; normalized by `Dense::forward` after the element-wise pass.
+            Activation::Softmax => v,
+            Activation::Sigmoid => 1.0 / (1.0 + (-v).exp()),
+            Activation::Tanh => v.tanh(),
+            Activation::None => v,
+        }
+    }
+}
+
 impl Dense {
     /// Creates a new `Dense` layer with random weights and biases.
     ///
@@ -44,17 +266,58 @@ impl Dense {
     /// * `out_features` - The number of output features (columns of the output tensor).
     /// * `activation` - The `Activation` function to use for this layer.
     pub fn new(in_features: usize, out_features: usize, activation: Activation) -> Self {
+        Self::with_init(in_features, out_features, activation, Init::Uniform)
+    }
+
+    /// Creates a new `Dense` layer whose parameters are sampled according to `init`.
+    pub fn with_init(
+        in_features: usize,
+        out_features: usize,
+        activation: Activation,
+        init: Init,
+    ) -> Self {
         Dense {
-            w: Tensor::random(in_features, out_features),
-            b: Tensor::random(1, out_features),
+            w: init.weights(in_features, out_features),
+            b: init.biases(out_features),
             input_cache: None,
             preact_cache: None,
             grad_w: None,
             grad_b: None,
+            mask: None,
+            dropout: None,
+            recompute_preact: false,
             activation,
         }
     }
 
+    /// Creates a `Dense` layer from existing weights and biases, e.g. pretrained or
+    /// hand-set values. Caches and gradients start empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `b` is not `(1, w.cols)`.
+    pub fn from_weights(w: Tensor, b: Tensor, activation: Activation) -> NeuroxResult<Dense> {
+        if b.shape() != (1, w.cols) {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "bias must be (1, {}), got {:?}",
+                w.cols,
+                b.shape()
+            )));
+        }
+        Ok(Dense {
+            w,
+            b,
+            input_cache: None,
+            preact_cache: None,
+            grad_w: None,
+            grad_b: None,
+            mask: None,
+            dropout: None,
+            recompute_preact: false,
+            activation,
+        })
+    }
+
     /// Performs the forward pass for the layer.
     ///
     /// Computes `activation(input @ w + b)`. The input and pre-activation
@@ -69,17 +332,31 @@ impl Dense {
     pub fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
         self.input_cache = Some(input.clone());
 
-        let z = ops::matmul(input, &self.w)?;
-        let z = z.add_row_broadcast(&self.b)?;
-        self.preact_cache = Some(z.clone());
+        let mut z = ops::matmul(input, &self.w)?;
+        if self.b.rows != 1 || self.b.cols != z.cols {
+            return Err(NeuroxError::ShapeMismatch(
+                "bias shape must be (1, cols)".into(),
+            ));
+        }
 
-        let out = match self.activation {
-            Activation::ReLU => activations::relu(&z),
-            Activation::Sigmoid => activations::sigmoid(&z),
-            Activation::Tanh => activations::tanh(&z),
-            Activation::None => z,
-        };
-        Ok(out)
+        // Bias add and activation fused into a single pass over the matmul output.
+        let mut out = z.zeros_like();
+        for i in 0..z.rows {
+            for j in 0..z.cols {
+                let idx = i * z.cols + j;
+                let v = z.data[idx] + self.b.data[j];
+                z.data[idx] = v;
+                out.data[idx] = self.activation.apply(v);
+            }
+        }
+        if let Activation::Softmax = self.activation {
+            out = activations::softmax(&out);
+        }
+        self.preact_cache = if self.recompute_preact { None } else { Some(z) };
+        match self.dropout.as_mut() {
+            Some(dropout) => Ok(dropout.forward(&out)),
+            None => Ok(out),
+        }
     }
 
     /// Performs the backward pass (backpropagation) for the layer.
@@ -98,10 +375,27 @@ impl Dense {
     /// # Returns
     /// The gradient with respect to this layer's input (`dL/dX`), with shape `(batch_size, in_features)`.
     pub fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
-        let pre = self
-            .preact_cache
+        let dropped;
+        let grad_out = match &self.dropout {
+            Some(dropout) => {
+                dropped = dropout.backward(grad_out)?;
+                &dropped
+            }
+            None => grad_out,
+        };
+
+        let input = self
+            .input_cache
             .as_ref()
             .expect("forward pass must be called before backward");
+        let recomputed;
+        let pre = match &self.preact_cache {
+            Some(pre) => pre,
+            None => {
+                recomputed = ops::add(&ops::matmul(input, &self.w)?, &self.b)?;
+                &recomputed
+            }
+        };
 
         // Gradient of the loss w.r.t. pre-activation (dL/dZ) using the chain rule.
         // dL/dZ = dL/dOut * dOut/dZ (element-wise)
@@ -110,6 +404,26 @@ impl Dense {
                 let g = activations::relu_grad(pre);
                 crate::ops::mul_elementwise(grad_out, &g)?
             }
+            Activation::LeakyReLU(alpha) => {
+                let g = activations::leaky_relu_grad(pre, alpha);
+                crate::ops::mul_elementwise(grad_out, &g)?
+            }
+            Activation::ELU(alpha) => {
+                let g = activations::elu_grad(pre, alpha);
+                crate::ops::mul_elementwise(grad_out, &g)?
+            }
+            Activation::GELU => {
+                let g = activations::gelu_grad(pre);
+                crate::ops::mul_elementwise(grad_out, &g)?
+            }
+            Activation::Softplus => {
+                let g = activations::softplus_grad(pre);
+                crate::ops::mul_elementwise(grad_out, &g)?
+            }
+            Activation::Softmax => {
+                let out = activations::softmax(pre);
+                activations::softmax_grad_jvp(&out, grad_out)
+            }
             Activation::Sigmoid => {
                 let out = activations::sigmoid(pre);
                 let g = activations::sigmoid_grad_from_out(&out);
@@ -124,8 +438,7 @@ impl Dense {
         };
 
         // Gradient for weights (dL/dW) = X^T * dL/dZ
-        let input = self.input_cache.as_ref().expect("no input cache");
-        let gw = ops::matmul(&input.transpose(), &dz)?;
+        let gw = ops::matmul_flags(input, &dz, true, false)?;
 
         // Gradient for biases (dL/dB) = sum of dL/dZ rows
         let mut gb = Tensor::zeros(1, dz.cols);
@@ -138,7 +451,7 @@ impl Dense {
         }
 
         // Gradient to pass to the previous layer (dL/dX) = dL/dZ * W^T
-        let grad_input = ops::matmul(&dz, &self.w.transpose())?;
+        let grad_input = ops::matmul_flags(&dz, &self.w, false, true)?;
 
         self.grad_w = Some(gw);
         self.grad_b = Some(gb);
@@ -161,6 +474,131 @@ impl Dense {
                 self.b.data[idx] -= lr * gb.data[idx];
             }
         }
+        self.apply_mask();
+    }
+
+    /// Re-samples the weights and biases according to `init`, keeping the layer's shape.
+    ///
+    /// Forward caches, stored gradients and the dropout mask are cleared. A pruning mask,
+    /// if any, is kept and re-applied, so pruned weights stay at zero.
+    pub fn reset_parameters(&mut self, init: Init) {
+        self.w = init.weights(self.w.rows, self.w.cols);
+        self.b = init.biases(self.w.cols);
+        self.clear_state();
+        self.apply_mask();
+    }
+
+    /// Zeros the smallest-magnitude `sparsity` fraction of the weights and records a mask
+    /// so that later optimizer steps keep them at zero. Biases are not pruned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sparsity` is not between `0.0` and `1.0`.
+    pub fn prune(&mut self, sparsity: f32) {
+        assert!(
+            (0.0..=1.0).contains(&sparsity),
+            "Sparsity must be between 0.0 and 1.0"
+        );
+        let n = self.w.data.len();
+        let k = ((n as f32) * sparsity).round() as usize;
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| self.w.data[a].abs().total_cmp(&self.w.data[b].abs()));
+
+        let mut mask = vec![true; n];
+        for &idx in &order[..k] {
+            mask[idx] = false;
+        }
+        self.mask = Some(mask);
+        self.apply_mask();
+    }
+
+    /// Re-applies the pruning mask, zeroing any pruned weights. No-op without a mask.
+    pub fn apply_mask(&mut self) {
+        if let Some(mask) = &self.mask {
+            for (w, &keep) in self.w.data.iter_mut().zip(mask) {
+                if !keep {
+                    *w = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Returns the pre-activation values `XW + B` cached by the last forward pass.
+    ///
+    /// Always `None` while `recompute_preact` is set.
+    pub fn preact(&self) -> Option<&Tensor> {
+        self.preact_cache.as_ref()
+    }
+
+    /// Returns rows `rows` of the weight gradient, i.e. the gradient for the weights fed by
+    /// those input features.
+    ///
+    /// Returns `None` before the first backward pass or if `rows` is out of bounds.
+    pub fn grad_slice(&self, rows: std::ops::Range<usize>) -> Option<Tensor> {
+        self.grad_w.as_ref()?.slice_rows(rows.start, rows.end).ok()
+    }
+
+    /// Keeps only the output neurons in `keep` (columns of `w`, `b` and the mask).
+    ///
+    /// Indices must already be validated. Caches and gradients are dropped since their
+    /// shapes no longer match.
+    pub(crate) fn select_outputs(&mut self, keep: &[usize]) {
+        let cols = self.w.cols;
+        let pick = |data: &[f32], rows: usize| -> Vec<f32> {
+            (0..rows)
+                .flat_map(|i| keep.iter().map(move |&j| data[i * cols + j]))
+                .collect()
+        };
+        self.w = Tensor::from_data(pick(&self.w.data, self.w.rows), self.w.rows, keep.len());
+        self.b = Tensor::from_data(pick(&self.b.data, 1), 1, keep.len());
+        if let Some(mask) = &self.mask {
+            let rows = self.w.rows;
+            self.mask = Some(
+                (0..rows)
+                    .flat_map(|i| keep.iter().map(move |&j| mask[i * cols + j]))
+                    .collect(),
+            );
+        }
+        self.clear_state();
+    }
+
+    /// Keeps only the input features in `keep` (rows of `w` and the mask).
+    ///
+    /// Indices must already be validated. Caches and gradients are dropped since their
+    /// shapes no longer match.
+    pub(crate) fn select_inputs(&mut self, keep: &[usize]) {
+        let cols = self.w.cols;
+        let data = keep
+            .iter()
+            .flat_map(|&i| self.w.data[i * cols..(i + 1) * cols].iter().copied())
+            .collect();
+        self.w = Tensor::from_data(data, keep.len(), cols);
+        if let Some(mask) = &self.mask {
+            self.mask = Some(
+                keep.iter()
+                    .flat_map(|&i| mask[i * cols..(i + 1) * cols].iter().copied())
+                    .collect(),
+            );
+        }
+        self.clear_state();
+    }
+
+    /// Drops forward caches and stored gradients.
+    fn clear_state(&mut self) {
+        self.input_cache = None;
+        self.preact_cache = None;
+        self.grad_w = None;
+        self.grad_b = None;
+        if let Some(dropout) = self.dropout.as_mut() {
+            dropout.mask = None;
+        }
+    }
+
+    /// Switches the layer's dropout (if any) between training and inference behaviour.
+    pub fn set_training(&mut self, training: bool) {
+        if let Some(dropout) = self.dropout.as_mut() {
+            dropout.training = training;
+        }
     }
 
     /// Returns the total number of trainable parameters in the layer (weights and biases).
@@ -168,3 +606,399 @@ impl Dense {
         self.w.data.len() + self.b.data.len()
     }
 }
+
+impl Layer for Dense {
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        Dense::forward(self, input)
+    }
+
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        Dense::backward(self, grad_out)
+    }
+
+    fn apply_gradients(&mut self, lr: f32) {
+        Dense::apply_gradients(self, lr)
+    }
+
+    fn num_params(&self) -> usize {
+        Dense::num_params(self)
+    }
+}
+
+impl Layer for Dropout {
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        Ok(Dropout::forward(self, input))
+    }
+
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        Dropout::backward(self, grad_out)
+    }
+
+    fn apply_gradients(&mut self, _lr: f32) {}
+
+    fn num_params(&self) -> usize {
+        0
+    }
+}
+
+/// Group normalization: normalizes each sample over groups of adjacent features.
+///
+/// The `features` columns are split into `num_groups` contiguous groups; within each
+/// sample and group the values are shifted to zero mean and scaled to unit variance,
+/// then each feature is scaled by `gamma` and shifted by `beta`. Statistics never mix
+/// samples, so unlike batch norm the result does not depend on the batch size.
+#[derive(Clone, Debug)]
+pub struct GroupNorm {
+    /// Number of feature groups.
+    pub num_groups: usize,
+    /// Per-feature scale of shape `(1, features)`, initialized to ones.
+    pub gamma: Tensor,
+    /// Per-feature shift of shape `(1, features)`, initialized to zeros.
+    pub beta: Tensor,
+    /// Added to the variance before the square root.
+    pub eps: f32,
+    /// Gradient of the loss with respect to `gamma`, computed during the backward pass.
+    pub grad_gamma: Option<Tensor>,
+    /// Gradient of the loss with respect to `beta`, computed during the backward pass.
+    pub grad_beta: Option<Tensor>,
+
+    // Normalized input and per-(sample, group) 1/std from the last forward pass.
+    x_hat: Option<Tensor>,
+    inv_std: Vec<f32>,
+}
+
+impl GroupNorm {
+    /// Creates a group norm over `features` columns with identity affine parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_groups` is zero or does not divide `features`.
+    pub fn new(features: usize, num_groups: usize) -> Self {
+        assert!(
+            num_groups > 0 && features.is_multiple_of(num_groups),
+            "features must be divisible by num_groups"
+        );
+        Self {
+            num_groups,
+            gamma: Tensor::from_data(vec![1.0; features], 1, features),
+            beta: Tensor::zeros(1, features),
+            eps: constants::NORM_EPS,
+            grad_gamma: None,
+            grad_beta: None,
+            x_hat: None,
+            inv_std: Vec::new(),
+        }
+    }
+}
+
+impl Layer for GroupNorm {
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `input` does not have `features` columns.
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        let features = self.gamma.cols;
+        if input.cols != features {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "GroupNorm expects {} features, got {}",
+                features, input.cols
+            )));
+        }
+        let size = features / self.num_groups;
+        let mut x_hat = Tensor::zeros(input.rows, features);
+        let mut out = Tensor::zeros(input.rows, features);
+        self.inv_std.clear();
+        for i in 0..input.rows {
+            for g in 0..self.num_groups {
+                let cols = g * size..(g + 1) * size;
+                let mean = cols.clone().map(|j| input.get(i, j)).sum::<f32>() / size as f32;
+                let var = cols
+                    .clone()
+                    .map(|j| (input.get(i, j) - mean).powi(2))
+                    .sum::<f32>()
+                    / size as f32;
+                let inv_std = 1.0 / (var + self.eps).sqrt();
+                self.inv_std.push(inv_std);
+                for j in cols {
+                    let xh = (input.get(i, j) - mean) * inv_std;
+                    x_hat.set(i, j, xh);
+                    out.set(i, j, self.gamma.data[j] * xh + self.beta.data[j]);
+                }
+            }
+        }
+        self.x_hat = Some(x_hat);
+        Ok(out)
+    }
+
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `grad_out` does not match the last output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `forward()` was not called before `backward()`.
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        let x_hat = self
+            .x_hat
+            .as_ref()
+            .expect("forward pass must be called before backward");
+        if grad_out.shape() != x_hat.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "grad_out must match the GroupNorm output shape".into(),
+            ));
+        }
+        let features = self.gamma.cols;
+        let size = features / self.num_groups;
+        let mut grad_gamma = Tensor::zeros(1, features);
+        let mut grad_beta = Tensor::zeros(1, features);
+        let mut grad_input = Tensor::zeros(grad_out.rows, features);
+        for i in 0..grad_out.rows {
+            for g in 0..self.num_groups {
+                let cols = g * size..(g + 1) * size;
+                let inv_std = self.inv_std[i * self.num_groups + g];
+                let mut sum_d = 0.0;
+                let mut sum_dx = 0.0;
+                for j in cols.clone() {
+                    let dy = grad_out.get(i, j);
+                    let xh = x_hat.get(i, j);
+                    grad_gamma.data[j] += dy * xh;
+                    grad_beta.data[j] += dy;
+                    let d = dy * self.gamma.data[j];
+                    sum_d += d;
+                    sum_dx += d * xh;
+                }
+                let n = size as f32;
+                for j in cols {
+                    let d = grad_out.get(i, j) * self.gamma.data[j];
+                    let xh = x_hat.get(i, j);
+                    grad_input.set(i, j, inv_std / n * (n * d - sum_d - xh * sum_dx));
+                }
+            }
+        }
+        self.grad_gamma = Some(grad_gamma);
+        self.grad_beta = Some(grad_beta);
+        Ok(grad_input)
+    }
+
+    fn apply_gradients(&mut self, lr: f32) {
+        if let Some(gg) = &self.grad_gamma {
+            for (p, g) in self.gamma.data.iter_mut().zip(&gg.data) {
+                *p -= lr * g;
+            }
+        }
+        if let Some(gb) = &self.grad_beta {
+            for (p, g) in self.beta.data.iter_mut().zip(&gb.data) {
+                *p -= lr * g;
+            }
+        }
+    }
+
+    fn num_params(&self) -> usize {
+        self.gamma.data.len() + self.beta.data.len()
+    }
+}
+
+/// Spectral normalization of a wrapped `Dense` layer.
+///
+/// Each forward pass divides the weights by an estimate of their largest singular value,
+/// so the layer's Lipschitz constant stays near 1. The estimate comes from power
+/// iteration whose left singular vector `u` persists across calls, so a single iteration
+/// per step is usually enough once training is under way. `layer.w` keeps the raw,
+/// unnormalized weights that the optimizer updates.
+pub struct SpectralNorm {
+    /// The wrapped layer, holding the raw weights.
+    pub layer: Dense,
+    /// Power iterations run per forward pass.
+    pub power_iters: usize,
+    // Estimates of the top left singular vector and singular value of `layer.w`.
+    u: Vec<f32>,
+    sigma: f32,
+}
+
+impl SpectralNorm {
+    /// Wraps `layer`, running one power iteration per forward pass.
+    ///
+    /// The initial singular vector is drawn from the crate RNG.
+    pub fn new(layer: Dense) -> Self {
+        let mut u = Tensor::random_normal(1, layer.w.rows, 0.0, 1.0).data;
+        crate::tensor::normalize(&mut u);
+        Self {
+            layer,
+            power_iters: 1,
+            u,
+            sigma: 1.0,
+        }
+    }
+
+    /// Returns the current spectral-norm estimate of the raw weights.
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+
+    /// Returns the normalized weights `w / sigma` used by the last forward pass.
+    pub fn weight(&self) -> Tensor {
+        let sigma = self.sigma;
+        self.layer.w.map(|v| v / sigma)
+    }
+
+    /// Runs `power_iters` power-iteration steps and refreshes `sigma`.
+    fn update_sigma(&mut self) {
+        let w = &self.layer.w;
+        let wt = w.transpose();
+        let mut v = Vec::new();
+        for _ in 0..self.power_iters.max(1) {
+            v = wt.mat_vec(&self.u);
+            crate::tensor::normalize(&mut v);
+            self.u = w.mat_vec(&v);
+            crate::tensor::normalize(&mut self.u);
+        }
+        let sigma: f32 = w.mat_vec(&v).iter().zip(&self.u).map(|(a, b)| a * b).sum();
+        self.sigma = if sigma > 0.0 { sigma } else { 1.0 };
+    }
+
+    /// Runs `f` with the wrapped layer's weights temporarily replaced by `w / sigma`.
+    fn with_normalized<R>(&mut self, f: impl FnOnce(&mut Dense) -> R) -> R {
+        let normalized = self.weight();
+        let raw = std::mem::replace(&mut self.layer.w, normalized);
+        let out = f(&mut self.layer);
+        self.layer.w = raw;
+        out
+    }
+}
+
+impl Layer for SpectralNorm {
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        self.update_sigma();
+        self.with_normalized(|layer| layer.forward(input))
+    }
+
+    /// Backpropagates through the normalized weights, treating `sigma` as a constant so
+    /// the raw weight gradient is the normalized one divided by `sigma`.
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        let grad_input = self.with_normalized(|layer| layer.backward(grad_out))?;
+        let sigma = self.sigma;
+        if let Some(gw) = self.layer.grad_w.as_mut() {
+            gw.data.iter_mut().for_each(|g| *g /= sigma);
+        }
+        Ok(grad_input)
+    }
+
+    fn apply_gradients(&mut self, lr: f32) {
+        self.layer.apply_gradients(lr);
+    }
+
+    fn num_params(&self) -> usize {
+        self.layer.num_params()
+    }
+}
+
+/// Weight normalization of a wrapped `Dense` layer.
+///
+/// The weights are reparameterized per output column as `w_j = g_j * v_j / ||v_j||`,
+/// decoupling each neuron's weight magnitude `g_j` from its direction `v_j`. Gradients
+/// flow to `v` and `g` (and the layer's bias); `layer.w` holds the effective weights
+/// and is recomputed from them on every forward pass.
+pub struct WeightNorm {
+    /// The wrapped layer; `layer.w` is overwritten with the effective weights.
+    pub layer: Dense,
+    /// Direction parameters of shape `(in_features, out_features)`.
+    pub v: Tensor,
+    /// Per-column scale parameters of shape `(1, out_features)`.
+    pub g: Tensor,
+    /// Gradient of the loss with respect to `v`, computed during the backward pass.
+    pub grad_v: Option<Tensor>,
+    /// Gradient of the loss with respect to `g`, computed during the backward pass.
+    pub grad_g: Option<Tensor>,
+}
+
+impl WeightNorm {
+    /// Wraps `layer`, initializing `v` to its weights and `g` to their column norms so the
+    /// effective weights are unchanged.
+    pub fn new(layer: Dense) -> Self {
+        let v = layer.w.clone();
+        let norms = column_norms(&v);
+        let g = Tensor::from_data(norms, 1, v.cols);
+        Self {
+            layer,
+            v,
+            g,
+            grad_v: None,
+            grad_g: None,
+        }
+    }
+
+    /// Recomputes `layer.w` from `v` and `g`. Columns of `v` with zero norm give zero weights.
+    fn sync_weights(&mut self) {
+        let norms = column_norms(&self.v);
+        let cols = self.v.cols;
+        for (idx, w) in self.layer.w.data.iter_mut().enumerate() {
+            let j = idx % cols;
+            *w = if norms[j] > 0.0 {
+                self.g.data[j] * self.v.data[idx] / norms[j]
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// Euclidean norm of each column of `t`.
+fn column_norms(t: &Tensor) -> Vec<f32> {
+    (0..t.cols)
+        .map(|j| (0..t.rows).map(|i| t.get(i, j).powi(2)).sum::<f32>().sqrt())
+        .collect()
+}
+
+impl Layer for WeightNorm {
+    fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        self.sync_weights();
+        self.layer.forward(input)
+    }
+
+    /// Backpropagates through the wrapped layer, then maps its weight gradient onto the
+    /// reparameterization: `dg_j = dw_j · v_j / ||v_j||` and
+    /// `dv_j = (g_j / ||v_j||) * dw_j - (g_j * dg_j / ||v_j||²) * v_j`.
+    fn backward(&mut self, grad_out: &Tensor) -> NeuroxResult<Tensor> {
+        let grad_input = self.layer.backward(grad_out)?;
+        let gw = self.layer.grad_w.as_ref().expect("backward sets grad_w");
+        let (rows, cols) = self.v.shape();
+        let norms = column_norms(&self.v);
+        let mut grad_v = Tensor::zeros(rows, cols);
+        let mut grad_g = Tensor::zeros(1, cols);
+        for (j, &n) in norms.iter().enumerate() {
+            if n == 0.0 {
+                continue;
+            }
+            let dg = (0..rows)
+                .map(|i| gw.get(i, j) * self.v.get(i, j))
+                .sum::<f32>()
+                / n;
+            grad_g.data[j] = dg;
+            let g = self.g.data[j];
+            for i in 0..rows {
+                let dv = g / n * gw.get(i, j) - g * dg / (n * n) * self.v.get(i, j);
+                grad_v.set(i, j, dv);
+            }
+        }
+        self.grad_v = Some(grad_v);
+        self.grad_g = Some(grad_g);
+        Ok(grad_input)
+    }
+
+    fn apply_gradients(&mut self, lr: f32) {
+        let step = |param: &mut Tensor, grad: &Option<Tensor>| {
+            if let Some(grad) = grad {
+                for (p, g) in param.data.iter_mut().zip(&grad.data) {
+                    *p -= lr * g;
+                }
+            }
+        };
+        step(&mut self.v, &self.grad_v);
+        step(&mut self.g, &self.grad_g);
+        step(&mut self.layer.b, &self.layer.grad_b);
+        self.sync_weights();
+    }
+
+    fn num_params(&self) -> usize {
+        self.v.data.len() + self.g.data.len() + self.layer.b.data.len()
+    }
+}
@@ -3,10 +3,11 @@
 //! This crate provides:
 //! - A `Tensor` type for numeric data (row-major)
 //! - Core tensor ops (matrix multiplication, element-wise add/mul, broadcasting)
-//! - Activation functions (ReLU, Sigmoid, Tanh, Softmax)
+//! - Activation functions (ReLU, LeakyReLU, ELU, GELU, Softplus, Sigmoid, Tanh, Softmax)
 //! - A `Dense` layer with backprop and cached activations
-//! - A sequential `Model` with forward pass and training via SGD/Adam
+//! - A sequential `Model` with forward pass and training via SGD/Adam/AdaGrad
 //! - Losses (MSE, Cross-Entropy), data utilities, and error types
+//! - Default numerical tolerances in `constants`
 //!
 //! All operations currently run on CPU and are designed for clarity and extensibility.
 //!
@@ -25,11 +26,14 @@
 //! ```
 
 pub mod activations;
+pub mod constants;
 pub mod data;
 pub mod errors;
 pub mod layers;
 pub mod loss;
 pub mod model;
+#[cfg(feature = "onnx")]
+mod onnx;
 pub mod optimizer;
 pub mod ops;
 pub mod tensor;
@@ -37,14 +41,14 @@ pub mod utils;
 
 // Convenient re-exports for common types and errors
 pub use crate::{model::Model, tensor::Tensor};
-pub use crate::layers::{Dense, Activation};
-pub use crate::optimizer::{SGD, Adam};
+pub use crate::layers::{Dense, Activation, Layer};
+pub use crate::optimizer::{SGD, Adam, AdaGrad, Optimizer};
 pub use crate::errors::{NeuroxError, NeuroxResult};
 
 /// Prelude with the most commonly used items.
 pub mod prelude {
     pub use crate::{Tensor, Model};
-    pub use crate::layers::{Dense, Activation};
-    pub use crate::optimizer::{SGD, Adam};
+    pub use crate::layers::{Dense, Activation, Layer};
+    pub use crate::optimizer::{SGD, Adam, AdaGrad, Optimizer};
     pub use crate::errors::{NeuroxError, NeuroxResult};
 }
@@ -1,32 +1,68 @@
-use crate::tensor::Tensor;
+use crate::{constants, ops, tensor::Tensor};
+
+/// How the squared errors in `mse_loss_with` are averaged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MseReduction {
+    /// Sum over features, mean over rows. This is what `mse_loss` uses.
+    #[default]
+    PerRow,
+    /// Mean over every element, i.e. over both rows and columns.
+    PerElement,
+}
 
 /// Mean Squared Error loss and gradient. inputs are (batch x features)
+///
+/// Squared errors are summed over features and averaged over the batch
+/// (`MseReduction::PerRow`); use `mse_loss_with` for per-element averaging.
 pub fn mse_loss(pred: &Tensor, target: &Tensor) -> (f32, Tensor) {
+    mse_loss_with(pred, target, MseReduction::PerRow)
+}
+
+/// Mean Squared Error loss and gradient with an explicit reduction.
+///
+/// The gradient is scaled by the same denominator as the loss, so it is always the exact
+/// derivative of the returned value.
+pub fn mse_loss_with(pred: &Tensor, target: &Tensor, mean_over: MseReduction) -> (f32, Tensor) {
     assert_eq!(pred.rows, target.rows);
     assert_eq!(pred.cols, target.cols);
+    let denom = match mean_over {
+        MseReduction::PerRow => pred.rows as f32,
+        MseReduction::PerElement => (pred.rows * pred.cols) as f32,
+    };
     let mut sum = 0.0;
     let mut grad = vec![0.0; pred.data.len()];
     for (i, g) in grad.iter_mut().enumerate().take(pred.data.len()) {
         let diff = pred.data[i] - target.data[i];
         sum += diff * diff;
-        *g = 2.0 * diff / (pred.rows as f32); // averaged over batch
+        *g = 2.0 * diff / denom;
     }
-    (
-        sum / (pred.rows as f32),
-        Tensor::from_data(grad, pred.rows, pred.cols),
-    )
+    (sum / denom, Tensor::from_data(grad, pred.rows, pred.cols))
 }
 
 /// Cross-entropy (assumes softmax already applied). target is one-hot or probabilities.
 /// returns (loss, grad wrt logits after softmax)
+///
+/// The loss is summed over the batch while the gradient `(p - t) / batch` is that of the
+/// batch-averaged loss with respect to the logits fed into the softmax. This holds for soft
+/// targets too (e.g. `[0.7, 0.3]` for distillation) as long as each target row sums to 1,
+/// since the softmax Jacobian then reduces `p * sum(t) - t` to `p - t`.
 pub fn cross_entropy_loss(prob: &Tensor, target: &Tensor) -> (f32, Tensor) {
+    cross_entropy_loss_with_eps(prob, target, constants::LOG_EPS)
+}
+
+/// Cross-entropy as in `cross_entropy_loss`, clamping probabilities to at least `eps`
+/// before the logarithm instead of `constants::LOG_EPS`.
+///
+/// A larger `eps` caps the loss of a confidently wrong prediction at `-ln(eps)`, which
+/// helps match frameworks that use a different clamp.
+pub fn cross_entropy_loss_with_eps(prob: &Tensor, target: &Tensor, eps: f32) -> (f32, Tensor) {
     assert_eq!(prob.rows, target.rows);
     assert_eq!(prob.cols, target.cols);
     let mut loss = 0.0;
     let mut grad = vec![0.0; prob.data.len()];
     for i in 0..prob.rows {
         for j in 0..prob.cols {
-            let p = (prob.get(i, j)).max(1e-7);
+            let p = (prob.get(i, j)).max(eps);
             let t = target.get(i, j);
             loss -= t * p.ln();
             grad[i * prob.cols + j] = (p - t) / (prob.rows as f32); // average over batch
@@ -34,3 +70,135 @@ pub fn cross_entropy_loss(prob: &Tensor, target: &Tensor) -> (f32, Tensor) {
     }
     (loss, Tensor::from_data(grad, prob.rows, prob.cols))
 }
+
+/// Negative log-likelihood of log-probabilities, e.g. from `activations::log_softmax`.
+/// target is one-hot or probabilities.
+///
+/// Equals `cross_entropy_loss` on the matching probabilities but never takes the
+/// logarithm of a (possibly underflowed) probability, so no clamp is needed and confidently
+/// wrong predictions keep their full loss. As there, the loss `-sum(t * log_p)` is summed
+/// over the batch and the gradient `(exp(log_p) * sum(t) - t) / batch` is that of the
+/// batch-averaged loss with respect to the logits fed into the log-softmax.
+///
+/// # Panics
+///
+/// Panics if `log_probs` and `target` differ in shape.
+pub fn nll_loss(log_probs: &Tensor, target: &Tensor) -> (f32, Tensor) {
+    assert_eq!(log_probs.rows, target.rows);
+    assert_eq!(log_probs.cols, target.cols);
+    let batch = log_probs.rows as f32;
+    let mut loss = 0.0;
+    let mut grad = log_probs.zeros_like();
+    for i in 0..log_probs.rows {
+        let row = i * log_probs.cols..(i + 1) * log_probs.cols;
+        let t_sum: f32 = target.data[row.clone()].iter().sum();
+        for idx in row {
+            let (lp, t) = (log_probs.data[idx], target.data[idx]);
+            if t != 0.0 {
+                loss -= t * lp;
+            }
+            grad.data[idx] = (lp.exp() * t_sum - t) / batch;
+        }
+    }
+    (loss, grad)
+}
+
+/// Binary cross-entropy (assumes sigmoid already applied). target holds 0/1 labels or
+/// probabilities, one independent output per column.
+///
+/// Like `cross_entropy_loss`, the loss `-(t ln p + (1 - t) ln(1 - p))` is summed over the
+/// batch while the gradient `(p - t) / batch` is that of the batch-averaged loss with
+/// respect to the logits fed into the sigmoid. Both `p` and `1 - p` are clamped to at
+/// least `constants::LOG_EPS` before the logarithm.
+///
+/// # Panics
+///
+/// Panics if `prob` and `target` differ in shape.
+pub fn bce_loss(prob: &Tensor, target: &Tensor) -> (f32, Tensor) {
+    assert_eq!(prob.rows, target.rows);
+    assert_eq!(prob.cols, target.cols);
+    let eps = constants::LOG_EPS;
+    let mut loss = 0.0;
+    let mut grad = vec![0.0; prob.data.len()];
+    for (i, g) in grad.iter_mut().enumerate() {
+        let p = prob.data[i];
+        let t = target.data[i];
+        loss -= t * p.max(eps).ln() + (1.0 - t) * (1.0 - p).max(eps).ln();
+        *g = (p - t) / prob.rows as f32;
+    }
+    (loss, Tensor::from_data(grad, prob.rows, prob.cols))
+}
+
+/// Class-weighted cross-entropy (assumes softmax already applied).
+///
+/// Each target entry is scaled by the weight of its class, e.g. from
+/// `data::class_weights`. With all weights `1.0` this equals `cross_entropy_loss`.
+/// As there, the loss is summed over the batch and the gradient is that of the
+/// batch-averaged loss with respect to the logits: `(p * sum(w * t) - w * t) / batch`.
+///
+/// # Panics
+///
+/// Panics if `prob` and `target` differ in shape or `weights` is not `(1, cols)`.
+pub fn weighted_cross_entropy_loss(
+    prob: &Tensor,
+    target: &Tensor,
+    weights: &Tensor,
+) -> (f32, Tensor) {
+    assert_eq!(prob.rows, target.rows);
+    assert_eq!(prob.cols, target.cols);
+    assert_eq!(weights.shape(), (1, prob.cols), "weights must be (1, cols)");
+    let mut loss = 0.0;
+    let mut grad = vec![0.0; prob.data.len()];
+    for i in 0..prob.rows {
+        let row_weight: f32 = (0..prob.cols)
+            .map(|j| weights.data[j] * target.get(i, j))
+            .sum();
+        for j in 0..prob.cols {
+            let p = (prob.get(i, j)).max(constants::LOG_EPS);
+            let wt = weights.data[j] * target.get(i, j);
+            loss -= wt * p.ln();
+            grad[i * prob.cols + j] = (p * row_weight - wt) / (prob.rows as f32);
+        }
+    }
+    (loss, Tensor::from_data(grad, prob.rows, prob.cols))
+}
+
+/// Token-level cross-entropy over flattened sequence logits, skipping padding.
+///
+/// `logits` has shape `(batch * seq, vocab)` and holds raw scores (Softmax is applied
+/// here). `labels[i]` is the target token of row `i`; rows whose label equals
+/// `pad_index` contribute neither loss nor gradient. The loss is the mean over the
+/// remaining tokens and the gradient `(p - onehot) / tokens` is its derivative with
+/// respect to the logits, with padded rows left at zero. If every row is padding the
+/// loss is `0.0`.
+///
+/// # Panics
+///
+/// Panics if `labels.len() != logits.rows` or a non-padding label is not a valid column.
+pub fn sequence_cross_entropy(
+    logits: &Tensor,
+    labels: &[usize],
+    pad_index: usize,
+) -> (f32, Tensor) {
+    assert_eq!(labels.len(), logits.rows, "need one label per row");
+    let tokens = labels.iter().filter(|&&l| l != pad_index).count();
+    let mut grad = logits.zeros_like();
+    if tokens == 0 {
+        return (0.0, grad);
+    }
+    let lse = ops::logsumexp(logits, 1).expect("axis 1 is valid");
+    let mut loss = 0.0;
+    for (i, &label) in labels.iter().enumerate() {
+        if label == pad_index {
+            continue;
+        }
+        assert!(label < logits.cols, "label {} out of range", label);
+        loss += lse.data[i] - logits.get(i, label);
+        for j in 0..logits.cols {
+            let p = (logits.get(i, j) - lse.data[i]).exp();
+            let t = if j == label { 1.0 } else { 0.0 };
+            grad.set(i, j, (p - t) / tokens as f32);
+        }
+    }
+    (loss / tokens as f32, grad)
+}
@@ -1,16 +1,139 @@
 //! Defines the main `Model` struct, its training loops, and evaluation utilities.
 
-use crate::errors::NeuroxResult;
-use crate::optimizer::{Adam, SGD};
+use crate::errors::{NeuroxError, NeuroxResult};
+use crate::optimizer::{AdaGrad, Adam, LrSchedule, Optimizer, SGD};
 use crate::{
-    layers::{Activation, Dense},
-    loss,
+    layers::{Activation, Dense, Dropout, LayerConfig},
+    loss, ops,
     tensor::Tensor,
+    utils,
 };
+use std::time::{Duration, Instant};
+
+/// Hyperparameters for `Model::train`.
+pub struct TrainConfig {
+    /// Number of passes over the dataset.
+    pub epochs: usize,
+    /// Number of rows per optimizer step.
+    pub batch_size: usize,
+    /// Base learning rate, reached at the end of warmup.
+    pub lr: f32,
+    /// Number of initial epochs over which the learning rate ramps linearly up to `lr`.
+    pub warmup_epochs: usize,
+    /// Learning-rate schedule applied after warmup; `None` keeps `lr` constant.
+    pub schedule: Option<Box<dyn LrSchedule>>,
+    /// L1 penalty strength: adds `l1 * sum(|w|)` to the loss, pushing weights to exactly zero.
+    pub l1: f32,
+    /// Whether the L1 penalty also covers biases. Off by default.
+    pub l1_biases: bool,
+    /// Factor the loss gradient is multiplied by before backprop, and the parameter
+    /// gradients divided by before the optimizer step. Keeps small gradients from
+    /// underflowing in low precision; `1.0` (the default) disables scaling.
+    pub loss_scale: f32,
+}
+
+impl TrainConfig {
+    /// Creates a config with a constant learning rate and no warmup.
+    pub fn new(epochs: usize, batch_size: usize, lr: f32) -> Self {
+        Self {
+            epochs,
+            batch_size,
+            lr,
+            warmup_epochs: 0,
+            schedule: None,
+            l1: 0.0,
+            l1_biases: false,
+            loss_scale: 1.0,
+        }
+    }
+
+    /// Returns the learning rate for `epoch` (0-based).
+    ///
+    /// During warmup this is `lr * (epoch + 1) / warmup_epochs`, so the last warmup epoch
+    /// trains at the full `lr`. Afterwards the schedule is queried with the number of
+    /// epochs elapsed since warmup ended, or `lr` is used if there is no schedule.
+    pub fn lr_at(&self, epoch: usize) -> f32 {
+        if epoch < self.warmup_epochs {
+            return self.lr * (epoch + 1) as f32 / self.warmup_epochs as f32;
+        }
+        match &self.schedule {
+            Some(schedule) => schedule.lr_at(epoch - self.warmup_epochs),
+            None => self.lr,
+        }
+    }
+
+    /// Returns the L1 penalty `l1 * sum(|w|)` of `layers`, including biases if `l1_biases`.
+    fn l1_penalty(&self, layers: &[Dense]) -> f32 {
+        if self.l1 == 0.0 {
+            return 0.0;
+        }
+        let norm: f32 = layers
+            .iter()
+            .map(|l| {
+                let mut n = l.w.abs().data.iter().sum::<f32>();
+                if self.l1_biases {
+                    n += l.b.abs().data.iter().sum::<f32>();
+                }
+                n
+            })
+            .sum();
+        self.l1 * norm
+    }
+
+    /// Divides the stored gradients of `layers` by `loss_scale`.
+    fn unscale(&self, layers: &mut [Dense]) {
+        if self.loss_scale == 1.0 {
+            return;
+        }
+        let inv = 1.0 / self.loss_scale;
+        for l in layers.iter_mut() {
+            for g in [&mut l.grad_w, &mut l.grad_b].into_iter().flatten() {
+                g.data.iter_mut().for_each(|v| *v *= inv);
+            }
+        }
+    }
+
+    /// Adds the L1 subgradient `l1 * sign(w)` to the stored gradients of `layers`.
+    fn apply_l1(&self, layers: &mut [Dense]) {
+        if self.l1 == 0.0 {
+            return;
+        }
+        let add = |grad: &mut Option<Tensor>, param: &Tensor| {
+            if let Some(g) = grad.as_mut() {
+                for (g, s) in g.data.iter_mut().zip(param.signum().data) {
+                    *g += self.l1 * s;
+                }
+            }
+        };
+        for l in layers.iter_mut() {
+            add(&mut l.grad_w, &l.w);
+            if self.l1_biases {
+                add(&mut l.grad_b, &l.b);
+            }
+        }
+    }
+}
+
+/// Activation that `Model::predict_proba` applies to the logits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputActivation {
+    /// Row-wise Softmax, for mutually exclusive classes.
+    #[default]
+    Softmax,
+    /// Element-wise Sigmoid, for independent (multi-label) outputs.
+    Sigmoid,
+    /// Return the logits unchanged.
+    None,
+}
 
 /// A sequential feed-forward neural network model.
 pub struct Model {
     pub layers: Vec<Dense>,
+    /// Activation applied by `predict_proba`, Softmax by default. `forward` always
+    /// returns raw logits for training.
+    pub output_activation: OutputActivation,
+    // Byte budget for cached pre-activations; see `set_checkpoint_memory_budget`.
+    checkpoint_budget: Option<usize>,
 }
 
 impl Model {
@@ -20,12 +143,198 @@ impl Model {
     /// * `layer_sizes` - A slice defining the number of neurons in each layer,
     ///   e.g., `&[784, 128, 10]` for a 784-input, 128-hidden, 10-output network.
     /// * `activation` - The `Activation` function to use for all hidden layers.
+    ///
+    /// The final layer is always linear (`Activation::None`) so that `forward` returns raw
+    /// logits; the training loops apply Softmax to them before the loss.
     pub fn new(layer_sizes: &[usize], activation: Activation) -> Self {
+        Self::build(layer_sizes, activation, |_, fan_in, fan_out, act| {
+            Dense::new(fan_in, fan_out, act)
+        })
+    }
+
+    /// Constructs a `Model` like `new`, initializing layer `i` from a seed derived from
+    /// `seed` and `i` alone.
+    ///
+    /// Each layer's weights therefore depend only on `seed`, its index and its own shape:
+    /// adding, removing or resizing other layers does not change them, and the crate's
+    /// global RNG (see `utils::set_seed`) is left untouched.
+    pub fn new_seeded(layer_sizes: &[usize], activation: Activation, seed: u64) -> Self {
+        Self::build(layer_sizes, activation, |i, fan_in, fan_out, act| {
+            utils::with_seed(utils::derive_seed(seed, i as u64), || {
+                Dense::new(fan_in, fan_out, act)
+            })
+        })
+    }
+
+    /// Shared layout of `new` and `new_seeded`: hidden layers use `activation` and the
+    /// last layer is linear. `make` receives the layer index, sizes and activation.
+    fn build<F>(layer_sizes: &[usize], activation: Activation, mut make: F) -> Self
+    where
+        F: FnMut(usize, usize, usize, Activation) -> Dense,
+    {
         let mut layers = Vec::new();
-        for win in layer_sizes.windows(2) {
-            layers.push(Dense::new(win[0], win[1], activation));
+        let last = layer_sizes.len().saturating_sub(2);
+        for (i, win) in layer_sizes.windows(2).enumerate() {
+            let act = if i == last {
+                Activation::None
+            } else {
+                activation
+            };
+            layers.push(make(i, win[0], win[1], act));
+        }
+        Self::from_layers(layers)
+    }
+
+    /// Wraps already-built layers, e.g. from `Dense::from_weights`, into a `Model`.
+    ///
+    /// The layers run in the given order, so each layer's `in_features` must match the
+    /// previous layer's `out_features`; a mismatch surfaces as an error from `forward`.
+    pub fn from_layers(layers: Vec<Dense>) -> Self {
+        Self {
+            layers,
+            output_activation: OutputActivation::Softmax,
+            checkpoint_budget: None,
         }
-        Self { layers }
+    }
+
+    /// Constructs a new `Model` from per-layer configurations.
+    ///
+    /// The first layer takes `input_size` features. Every entry of `configs` adds a `Dense`
+    /// layer with the given size and activation, followed by dropout when `dropout` is set.
+    /// Unlike `new`, the last layer uses exactly the activation given.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if a dropout rate is not in `[0.0, 1.0)`.
+    pub fn from_configs(input_size: usize, configs: &[LayerConfig]) -> NeuroxResult<Self> {
+        let mut layers = Vec::new();
+        let mut fan_in = input_size;
+        for (i, config) in configs.iter().enumerate() {
+            let mut layer = Dense::new(fan_in, config.size, config.activation);
+            if let Some(rate) = config.dropout {
+                if !(0.0..1.0).contains(&rate) {
+                    return Err(NeuroxError::InvalidArgument(format!(
+                        "layer {} dropout rate {} is not in [0, 1)",
+                        i, rate
+                    )));
+                }
+                layer.dropout = Some(Dropout::new(rate));
+            }
+            layers.push(layer);
+            fan_in = config.size;
+        }
+        Ok(Self::from_layers(layers))
+    }
+
+    /// Switches every layer between training mode (dropout active) and inference mode.
+    pub fn set_training(&mut self, training: bool) {
+        for layer in self.layers.iter_mut() {
+            layer.set_training(training);
+        }
+    }
+
+    /// Runs `f` with every layer switched to `training` mode, then restores each layer's
+    /// previous mode.
+    fn with_training<R>(&mut self, training: bool, f: impl FnOnce(&mut Self) -> R) -> R {
+        let previous: Vec<Option<bool>> = self
+            .layers
+            .iter()
+            .map(|l| l.dropout.as_ref().map(|d| d.training))
+            .collect();
+        self.set_training(training);
+        let result = f(self);
+        for (layer, prev) in self.layers.iter_mut().zip(previous) {
+            if let (Some(dropout), Some(prev)) = (layer.dropout.as_mut(), prev) {
+                dropout.training = prev;
+            }
+        }
+        result
+    }
+
+    /// Predicts class probabilities by applying the output activation to the logits.
+    ///
+    /// Uses `output_activation`, Softmax by default. Dropout is disabled for the pass,
+    /// so the result is deterministic.
+    pub fn predict_proba(&mut self, x: &Tensor) -> NeuroxResult<Tensor> {
+        let logits = self.with_training(false, |m| m.forward(x))?;
+        Ok(self.apply_output_activation(&logits))
+    }
+
+    /// Predicts class probabilities like `predict_proba`, forwarding `x` in chunks of at
+    /// most `chunk` rows to bound the size of intermediate activations.
+    ///
+    /// Rows are independent at inference, so the result equals `predict_proba(x)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `chunk` is zero, or any error raised by
+    /// the forward pass.
+    pub fn predict_proba_chunked(&mut self, x: &Tensor, chunk: usize) -> NeuroxResult<Tensor> {
+        if chunk == 0 {
+            return Err(NeuroxError::InvalidArgument(
+                "chunk size must be positive".into(),
+            ));
+        }
+        if x.rows <= chunk {
+            return self.predict_proba(x);
+        }
+        let parts = (0..x.rows)
+            .step_by(chunk)
+            .map(|start| self.predict_proba(&x.slice_rows(start, (start + chunk).min(x.rows))?))
+            .collect::<NeuroxResult<Vec<Tensor>>>()?;
+        ops::vstack(&parts)
+    }
+
+    /// Applies `output_activation` to `logits`.
+    fn apply_output_activation(&self, logits: &Tensor) -> Tensor {
+        match self.output_activation {
+            OutputActivation::Softmax => crate::activations::softmax(logits),
+            OutputActivation::Sigmoid => crate::activations::sigmoid(logits),
+            OutputActivation::None => logits.clone(),
+        }
+    }
+
+    /// Estimates predictive uncertainty with Monte Carlo dropout.
+    ///
+    /// Runs `samples` forward passes with dropout active and returns the per-element
+    /// `(mean, variance)` of the outputs after the output activation (see
+    /// `predict_proba`). Layers without dropout contribute no randomness, so a model
+    /// without dropout yields zero variance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `samples` is zero.
+    pub fn predict_proba_mc(
+        &mut self,
+        x: &Tensor,
+        samples: usize,
+    ) -> NeuroxResult<(Tensor, Tensor)> {
+        if samples == 0 {
+            return Err(NeuroxError::InvalidArgument(
+                "samples must be positive".into(),
+            ));
+        }
+        let draws = self.with_training(true, |m| {
+            (0..samples)
+                .map(|_| m.forward(x).map(|z| m.apply_output_activation(&z)))
+                .collect::<NeuroxResult<Vec<Tensor>>>()
+        })?;
+
+        let n = samples as f32;
+        let (rows, cols) = draws[0].shape();
+        let mut mean = Tensor::zeros(rows, cols);
+        for d in &draws {
+            for (m, v) in mean.data.iter_mut().zip(&d.data) {
+                *m += v / n;
+            }
+        }
+        let mut var = Tensor::zeros(rows, cols);
+        for d in &draws {
+            for ((s, v), m) in var.data.iter_mut().zip(&d.data).zip(&mean.data) {
+                *s += (v - m) * (v - m) / n;
+            }
+        }
+        Ok((mean, var))
     }
 
     /// Performs a forward pass through the entire network.
@@ -36,6 +345,9 @@ impl Model {
     /// # Returns
     /// A `Result` containing the output tensor (logits).
     pub fn forward(&mut self, input: &Tensor) -> NeuroxResult<Tensor> {
+        if let Some(budget) = self.checkpoint_budget {
+            self.plan_checkpoints(input.rows, budget);
+        }
         let mut x = input.clone();
         for layer in self.layers.iter_mut() {
             x = layer.forward(&x)?;
@@ -43,43 +355,395 @@ impl Model {
         Ok(x)
     }
 
-    /// Trains the model using the SGD optimizer.
+    /// Limits the activation memory cached for `backward` to about `bytes` per forward pass.
     ///
-    /// This method iterates through the dataset for a specified number of epochs,
-    /// performing forward and backward passes and updating model weights.
-    /// Assumes a Softmax Cross-Entropy loss for training.
-    pub fn train_sgd(
+    /// Layer inputs are always cached, as the weight gradients need them, so their
+    /// `(batch, in_features)` buffers are counted against the budget first. The layers are
+    /// then visited in order and each keeps its `(batch, out_features)` pre-activation only
+    /// while the running total stays within the budget; the rest set
+    /// `Dense::recompute_preact` and recompute it during `backward`. Gradients are
+    /// unchanged, only extra compute is spent. If the inputs alone exceed the budget, no
+    /// pre-activation is cached. Pass `usize::MAX` to cache every layer again.
+    pub fn set_checkpoint_memory_budget(&mut self, bytes: usize) {
+        self.checkpoint_budget = Some(bytes);
+    }
+
+    /// Decides which layers cache their pre-activation for a batch of `rows` samples.
+    fn plan_checkpoints(&mut self, rows: usize, budget: usize) {
+        let float = std::mem::size_of::<f32>();
+        let mut used: usize = self.layers.iter().map(|l| rows * l.w.rows * float).sum();
+        for layer in self.layers.iter_mut() {
+            let bytes = rows * layer.w.cols * float;
+            layer.recompute_preact = used + bytes > budget;
+            if !layer.recompute_preact {
+                used += bytes;
+            }
+        }
+    }
+
+    /// Returns the output of layer `layer` (after its activation), for use as features.
+    ///
+    /// Only layers `0..=layer` are run, with dropout disabled; e.g. `layer = 0` on a
+    /// `[3, 4, 2]` model gives the `(n, 4)` hidden representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `layer` is not a valid layer index, or
+    /// any error raised by the forward pass.
+    pub fn extract_features(&mut self, x: &Tensor, layer: usize) -> NeuroxResult<Tensor> {
+        if layer >= self.layers.len() {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "layer {} out of range for a model with {} layers",
+                layer,
+                self.layers.len()
+            )));
+        }
+        self.with_training(false, |m| {
+            let mut out = x.clone();
+            for l in m.layers[..=layer].iter_mut() {
+                out = l.forward(&out)?;
+            }
+            Ok(out)
+        })
+    }
+
+    /// Trains a linear probe: a new `Dense` head on the frozen output of `feature_layer`.
+    ///
+    /// Features are extracted once with `extract_features`, so the model itself is left
+    /// untouched, and a linear `(features, num_classes)` layer is trained on them with
+    /// `train` (Softmax Cross-Entropy) according to `config`. Adam and AdaGrad resize their
+    /// state to the head on the first step, so `opt` may be created for any layers.
+    ///
+    /// # Returns
+    /// The trained head; its `forward` on the extracted features yields class logits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `feature_layer` is out of range,
+    /// `NeuroxError::ShapeMismatch` if `y` is not `(x.rows, num_classes)`, or any error
+    /// raised during training.
+    pub fn linear_probe(
+        &mut self,
+        feature_layer: usize,
+        num_classes: usize,
+        x: &Tensor,
+        y: &Tensor,
+        config: &TrainConfig,
+        opt: &mut dyn Optimizer,
+    ) -> NeuroxResult<Dense> {
+        let features = self.extract_features(x, feature_layer)?;
+        if y.shape() != (x.rows, num_classes) {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "probe targets must be ({}, {}), got {:?}",
+                x.rows,
+                num_classes,
+                y.shape()
+            )));
+        }
+        let mut probe = Model::from_layers(vec![Dense::new(
+            features.cols,
+            num_classes,
+            Activation::None,
+        )]);
+        probe.train(&features, y, config, opt)?;
+        Ok(probe.layers.remove(0))
+    }
+
+    /// Runs a forward pass while timing each layer.
+    ///
+    /// Returns `(layer_index, duration)` for every layer, in order. The forward caches are
+    /// populated exactly as in `forward`.
+    pub fn profile_forward(&mut self, x: &Tensor) -> NeuroxResult<Vec<(usize, Duration)>> {
+        let mut timings = Vec::with_capacity(self.layers.len());
+        let mut out = x.clone();
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            let start = Instant::now();
+            out = layer.forward(&out)?;
+            timings.push((i, start.elapsed()));
+        }
+        Ok(timings)
+    }
+
+    /// Backpropagates `grad` (dL/d output) through all layers in reverse order.
+    ///
+    /// Each layer stores its parameter gradients; the returned tensor is the gradient
+    /// with respect to the model input. `forward` must have been called first.
+    pub fn backward(&mut self, grad: &Tensor) -> NeuroxResult<Tensor> {
+        let mut upstream_grad = grad.clone();
+        for layer in self.layers.iter_mut().rev() {
+            upstream_grad = layer.backward(&upstream_grad)?;
+        }
+        Ok(upstream_grad)
+    }
+
+    /// Computes the gradient of the Softmax Cross-Entropy loss with respect to the input `x`.
+    ///
+    /// The loss gradient is averaged over the batch, as in training, and dropout is
+    /// disabled. As a side effect the layers' parameter gradients are overwritten.
+    pub fn input_gradient(&mut self, x: &Tensor, y: &Tensor) -> NeuroxResult<Tensor> {
+        self.with_training(false, |m| {
+            let preds = m.forward(x)?;
+            let probs = crate::activations::softmax(&preds);
+            let (_loss, grad) = loss::cross_entropy_loss(&probs, y);
+            m.backward(&grad)
+        })
+    }
+
+    /// Computes the Jacobian of the model output with respect to a single input row.
+    ///
+    /// Returns an `(out_features, in_features)` matrix whose row `k` is `d out_k / d x`,
+    /// obtained by backpropagating a one-hot gradient for each output with dropout disabled.
+    /// As a side effect the layers' parameter gradients are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `x` does not have exactly one row.
+    pub fn jacobian(&mut self, x: &Tensor) -> NeuroxResult<Tensor> {
+        if x.rows != 1 {
+            return Err(NeuroxError::InvalidArgument(
+                "jacobian expects a single input row".into(),
+            ));
+        }
+        self.with_training(false, |m| {
+            let out = m.forward(x)?;
+            let mut jac = Tensor::zeros(out.cols, x.cols);
+            for k in 0..out.cols {
+                let mut seed = Tensor::zeros(1, out.cols);
+                seed.set(0, k, 1.0);
+                let row = m.backward(&seed)?;
+                for j in 0..x.cols {
+                    jac.set(k, j, row.get(0, j));
+                }
+            }
+            Ok(jac)
+        })
+    }
+
+    /// Estimates per-feature importance as the mean absolute input gradient over all samples.
+    ///
+    /// Returns a `(1, in_features)` tensor whose entry `j` is the average of `|dL_i/dx_ij|`,
+    /// where `L_i` is the loss of sample `i`. Features the model ignores score zero.
+    pub fn feature_importance(&mut self, x: &Tensor, y: &Tensor) -> NeuroxResult<Tensor> {
+        let grad = self.input_gradient(x, y)?;
+        // `grad` holds dL_i/dx_i / n, so summing magnitudes yields the per-sample mean.
+        let mut out = Tensor::zeros(1, grad.cols);
+        for i in 0..grad.rows {
+            for j in 0..grad.cols {
+                out.data[j] += grad.get(i, j).abs();
+            }
+        }
+        Ok(out)
+    }
+
+    /// Generates adversarial inputs with the Fast Gradient Sign Method.
+    ///
+    /// Returns `x + epsilon * sign(dL/dx)` for the Softmax Cross-Entropy loss against `y`,
+    /// nudging every feature in the direction that increases the loss. As a side effect the
+    /// layers' parameter gradients are overwritten.
+    pub fn fgsm(&mut self, x: &Tensor, y: &Tensor, epsilon: f32) -> NeuroxResult<Tensor> {
+        let step = self.input_gradient(x, y)?.signum().map(|s| s * epsilon);
+        ops::add(x, &step)
+    }
+
+    /// Trains the model with any `Optimizer` according to `config`.
+    ///
+    /// Before each epoch the optimizer's learning rate is set from `config.lr_at(epoch)`.
+    /// Batches are taken in order without shuffling. Assumes a Softmax Cross-Entropy loss,
+    /// plus the L1 penalty if `config.l1` is non-zero. With a `config.loss_scale` other
+    /// than `1.0`, gradients are unscaled before the L1 term is added.
+    ///
+    /// # Returns
+    /// The mean per-sample loss of each epoch, plus the L1 penalty at the end of the epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `config.batch_size` is zero, or any error
+    /// raised by the forward or backward pass.
+    pub fn train(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        config: &TrainConfig,
+        opt: &mut dyn Optimizer,
+    ) -> NeuroxResult<Vec<f32>> {
+        self.fit(x, y, config, opt, |preds, by| {
+            let probs = crate::activations::softmax(preds);
+            loss::cross_entropy_loss(&probs, by)
+        })
+    }
+
+    /// Trains a single-output binary classifier with a Sigmoid + Binary Cross-Entropy loss.
+    ///
+    /// `y` holds one `0.0`/`1.0` label per row, shape `(n, 1)`. Otherwise this behaves like
+    /// `train`, including the learning-rate schedule, L1 penalty and loss scaling. `forward`
+    /// still returns logits; set `output_activation` to `OutputActivation::Sigmoid` to get
+    /// probabilities from `predict_proba`.
+    ///
+    /// # Returns
+    /// The mean per-sample loss of each epoch, plus the L1 penalty at the end of the epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if the model does not have exactly one output
+    /// or `config.batch_size` is zero, `NeuroxError::ShapeMismatch` if `y` is not
+    /// `(x.rows, 1)`, or any error raised by the forward or backward pass.
+    pub fn train_binary(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        config: &TrainConfig,
+        opt: &mut dyn Optimizer,
+    ) -> NeuroxResult<Vec<f32>> {
+        if self.layers.last().map(|l| l.w.cols) != Some(1) {
+            return Err(NeuroxError::InvalidArgument(
+                "train_binary needs a model with a single output".into(),
+            ));
+        }
+        if y.shape() != (x.rows, 1) {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "binary targets must be ({}, 1), got {:?}",
+                x.rows,
+                y.shape()
+            )));
+        }
+        self.fit(x, y, config, opt, |preds, by| {
+            let probs = crate::activations::sigmoid(preds);
+            loss::bce_loss(&probs, by)
+        })
+    }
+
+    /// Trains the model with a caller-supplied loss, e.g. `loss::mse_loss` for regression.
+    ///
+    /// `loss_fn(outputs, targets)` receives the raw model outputs of a batch and returns
+    /// the batch-averaged loss and its gradient with respect to those outputs, which is fed
+    /// straight into `backward`. Batches are taken in order at the optimizer's current
+    /// learning rate.
+    ///
+    /// # Returns
+    /// The mean per-sample loss of each epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `batch_size` is zero, or any error raised
+    /// by the forward or backward pass.
+    pub fn train_with_loss<F>(
         &mut self,
         x: &Tensor,
         y: &Tensor,
         epochs: usize,
         batch_size: usize,
-        lr: f32,
-    ) -> NeuroxResult<()> {
-        let opt = SGD::new(lr);
-        for _epoch in 0..epochs {
-            // Naive batching without shuffling for simplicity.
-            for start in (0..x.rows).step_by(batch_size) {
-                let end = (start + batch_size).min(x.rows);
-                let bx = slice_rows(x, start, end)?;
-                let by = slice_rows(y, start, end)?;
+        opt: &mut dyn Optimizer,
+        mut loss_fn: F,
+    ) -> NeuroxResult<Vec<f32>>
+    where
+        F: FnMut(&Tensor, &Tensor) -> (f32, Tensor),
+    {
+        let config = TrainConfig::new(epochs, batch_size, opt.lr());
+        self.fit(x, y, &config, opt, |preds, by| {
+            // `fit` expects the batch loss summed over samples.
+            let (batch_loss, grad) = loss_fn(preds, by);
+            (batch_loss * preds.rows as f32, grad)
+        })
+    }
+
+    /// Shared mini-batch loop of `train`, `train_binary` and `train_with_loss`.
+    ///
+    /// `loss_fn` maps a batch of logits and targets to the summed batch loss and the
+    /// gradient of the batch-averaged loss with respect to the logits.
+    fn fit<F>(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        config: &TrainConfig,
+        opt: &mut dyn Optimizer,
+        mut loss_fn: F,
+    ) -> NeuroxResult<Vec<f32>>
+    where
+        F: FnMut(&Tensor, &Tensor) -> (f32, Tensor),
+    {
+        if config.batch_size == 0 {
+            return Err(NeuroxError::InvalidArgument(
+                "batch_size must be positive".into(),
+            ));
+        }
+        let mut history = Vec::with_capacity(config.epochs);
+        for epoch in 0..config.epochs {
+            opt.set_lr(config.lr_at(epoch));
+            let mut epoch_loss = 0.0;
+            for start in (0..x.rows).step_by(config.batch_size) {
+                let end = (start + config.batch_size).min(x.rows);
+                let bx = x.slice_rows(start, end)?;
+                let by = y.slice_rows(start, end)?;
 
                 // Forward pass
                 let preds = self.forward(&bx)?;
-                // Assume Softmax Cross-Entropy loss
-                let probs = crate::activations::softmax(&preds);
-                let (_loss, grad) = loss::cross_entropy_loss(&probs, &by);
+                let (batch_loss, grad) = loss_fn(&preds, &by);
+                epoch_loss += batch_loss;
 
                 // Backward pass through layers in reverse order
-                let mut upstream_grad = grad;
-                for layer in self.layers.iter_mut().rev() {
-                    upstream_grad = layer.backward(&upstream_grad)?;
+                if config.loss_scale == 1.0 {
+                    self.backward(&grad)?;
+                } else {
+                    self.backward(&grad.map(|g| g * config.loss_scale))?;
+                    config.unscale(&mut self.layers);
                 }
+                config.apply_l1(&mut self.layers);
 
                 // Update weights
                 opt.step(&mut self.layers);
             }
+            let penalty = config.l1_penalty(&self.layers);
+            history.push(epoch_loss / x.rows.max(1) as f32 + penalty);
+        }
+        Ok(history)
+    }
+
+    /// Performs a single optimizer step on one batch, for online or streaming training.
+    ///
+    /// Runs forward, backward and `opt.step` once, with no epoch loop, shuffling or
+    /// learning-rate schedule. Assumes a Softmax Cross-Entropy loss.
+    ///
+    /// # Returns
+    /// The mean per-sample loss of the batch, measured before the update.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `y` does not match the model output shape,
+    /// or any error raised by the forward or backward pass.
+    pub fn partial_fit(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        opt: &mut dyn Optimizer,
+    ) -> NeuroxResult<f32> {
+        let preds = self.forward(x)?;
+        if preds.shape() != y.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "targets must match the model output shape".into(),
+            ));
         }
+        let probs = crate::activations::softmax(&preds);
+        let (batch_loss, grad) = loss::cross_entropy_loss(&probs, y);
+        self.backward(&grad)?;
+        opt.step(&mut self.layers);
+        Ok(batch_loss / x.rows.max(1) as f32)
+    }
+
+    /// Trains the model using the SGD optimizer.
+    ///
+    /// This method iterates through the dataset for a specified number of epochs,
+    /// performing forward and backward passes and updating model weights.
+    /// Assumes a Softmax Cross-Entropy loss for training.
+    pub fn train_sgd(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        epochs: usize,
+        batch_size: usize,
+        lr: f32,
+    ) -> NeuroxResult<()> {
+        let config = TrainConfig::new(epochs, batch_size, lr);
+        self.train(x, y, &config, &mut SGD::new(lr))?;
         Ok(())
     }
 
@@ -97,27 +761,274 @@ impl Model {
         lr: f32,
     ) -> NeuroxResult<()> {
         let mut adam = Adam::new(lr, &self.layers);
-        for _epoch in 0..epochs {
-            for start in (0..x.rows).step_by(batch_size) {
-                let end = (start + batch_size).min(x.rows);
-                let bx = slice_rows(x, start, end)?;
-                let by = slice_rows(y, start, end)?;
+        let config = TrainConfig::new(epochs, batch_size, lr);
+        self.train(x, y, &config, &mut adam)?;
+        Ok(())
+    }
 
-                let preds = self.forward(&bx)?;
-                let probs = crate::activations::softmax(&preds);
-                let (_loss, grad) = loss::cross_entropy_loss(&probs, &by);
+    /// Trains the model using the AdaGrad optimizer.
+    ///
+    /// This method iterates through the dataset for a specified number of epochs,
+    /// performing forward and backward passes and updating model weights.
+    /// Assumes a Softmax Cross-Entropy loss for training.
+    pub fn train_adagrad(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        epochs: usize,
+        batch_size: usize,
+        lr: f32,
+    ) -> NeuroxResult<()> {
+        let mut adagrad = AdaGrad::new(lr, &self.layers);
+        let config = TrainConfig::new(epochs, batch_size, lr);
+        self.train(x, y, &config, &mut adagrad)?;
+        Ok(())
+    }
 
-                let mut upstream_grad = grad;
-                for layer in self.layers.iter_mut().rev() {
-                    upstream_grad = layer.backward(&upstream_grad)?;
-                }
+    /// Continues training with an existing Adam optimizer at its current learning rate.
+    ///
+    /// Unlike `train_adam`, which starts from a fresh optimizer each call, the caller keeps
+    /// `adam` between calls, so its moment estimates and step count `t` carry over.
+    /// Assumes a Softmax Cross-Entropy loss for training.
+    pub fn train_adam_continue(
+        &mut self,
+        adam: &mut Adam,
+        x: &Tensor,
+        y: &Tensor,
+        epochs: usize,
+        batch_size: usize,
+    ) -> NeuroxResult<()> {
+        let config = TrainConfig::new(epochs, batch_size, adam.lr);
+        self.train(x, y, &config, adam)?;
+        Ok(())
+    }
+
+    /// Returns the cross-entropy loss of each sample individually, without batch averaging.
+    ///
+    /// The logits are always softmaxed, whatever `output_activation` is set to, and dropout
+    /// is disabled. Sorting the result surfaces the hardest (or mislabeled) examples.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `y` does not match the model output shape.
+    pub fn per_sample_loss(&mut self, x: &Tensor, y: &Tensor) -> NeuroxResult<Vec<f32>> {
+        let logits = self.with_training(false, |m| m.forward(x))?;
+        let probs = crate::activations::softmax(&logits);
+        if probs.shape() != y.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "targets must match the model output shape".into(),
+            ));
+        }
+        Ok((0..probs.rows)
+            .map(|i| {
+                (0..probs.cols)
+                    .map(|j| -y.get(i, j) * probs.get(i, j).max(crate::constants::LOG_EPS).ln())
+                    .sum()
+            })
+            .collect())
+    }
+
+    /// Computes single-label classification accuracy by comparing the argmax of the logits
+    /// with the argmax of the one-hot targets `y`.
+    ///
+    /// Samples whose true class equals `ignore_index` (e.g. a padding label) are excluded
+    /// from both the numerator and the denominator. Dropout is disabled for the pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `y` does not match the model output shape, or
+    /// `NeuroxError::InvalidArgument` if `ignore_index` is not a valid class or every sample
+    /// is ignored.
+    pub fn accuracy(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        ignore_index: Option<usize>,
+    ) -> NeuroxResult<f32> {
+        if let Some(ignored) = ignore_index
+            && ignored >= y.cols
+        {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "ignore_index {} out of range for {} classes",
+                ignored, y.cols
+            )));
+        }
+        let logits = self.with_training(false, |m| m.forward(x))?;
+        if logits.shape() != y.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "targets must match the model output shape".into(),
+            ));
+        }
+
+        let mut correct = 0usize;
+        let mut total = 0usize;
+        for (pred, truth) in logits.argmax_rows().into_iter().zip(y.argmax_rows()) {
+            if Some(truth) == ignore_index {
+                continue;
+            }
+            total += 1;
+            if pred == truth {
+                correct += 1;
+            }
+        }
+        if total == 0 {
+            return Err(NeuroxError::InvalidArgument(
+                "every sample is ignored".into(),
+            ));
+        }
+        Ok(correct as f32 / total as f32)
+    }
+
+    /// Computes accuracy for multilabel targets, where each output is an independent binary decision.
+    ///
+    /// Applies a sigmoid to the logits, predicts positive where the probability is at least
+    /// `threshold`, and returns the fraction of all `(sample, label)` decisions that match `y`
+    /// (entries of `y` at or above `0.5` count as positive). Dropout is disabled for the
+    /// pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `y` does not match the shape of the model output.
+    pub fn multilabel_accuracy(
+        &mut self,
+        x: &Tensor,
+        y: &Tensor,
+        threshold: f32,
+    ) -> NeuroxResult<f32> {
+        let logits = self.with_training(false, |m| m.forward(x))?;
+        if logits.shape() != y.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "targets must match the model output shape".into(),
+            ));
+        }
+        let probs = crate::activations::sigmoid(&logits);
+        let correct = probs
+            .data
+            .iter()
+            .zip(&y.data)
+            .filter(|&(&p, &t)| (p >= threshold) == (t >= 0.5))
+            .count();
+        Ok(correct as f32 / probs.data.len() as f32)
+    }
 
-                adam.step(&mut self.layers);
+    /// Applies magnitude pruning to every layer, zeroing the smallest `sparsity` fraction
+    /// of each layer's weights. The pruned weights stay zero through subsequent training.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sparsity` is not between `0.0` and `1.0`.
+    pub fn prune(&mut self, sparsity: f32) {
+        for layer in self.layers.iter_mut() {
+            layer.prune(sparsity);
+        }
+    }
+
+    /// Finds ReLU neurons that output zero for every sample in `x`.
+    ///
+    /// Runs a forward pass with dropout disabled and returns, for each layer, the indices
+    /// of units whose activated output is `0` on every row. The outputs are inspected as
+    /// they are produced, so this works even when `set_checkpoint_memory_budget` keeps
+    /// layers from caching their pre-activations. Non-ReLU layers get an empty list, as
+    /// their outputs are not clamped to zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by the forward pass.
+    pub fn dead_units(&mut self, x: &Tensor) -> NeuroxResult<Vec<Vec<usize>>> {
+        self.with_training(false, |m| {
+            let mut out = x.clone();
+            let mut dead = Vec::with_capacity(m.layers.len());
+            for layer in m.layers.iter_mut() {
+                out = layer.forward(&out)?;
+                dead.push(match layer.activation {
+                    Activation::ReLU => (0..out.cols)
+                        .filter(|&j| (0..out.rows).all(|i| out.get(i, j) == 0.0))
+                        .collect(),
+                    _ => Vec::new(),
+                });
             }
+            Ok(dead)
+        })
+    }
+
+    /// Suggests the largest power-of-two batch size whose activations fit `mem_budget_bytes`.
+    ///
+    /// A rough heuristic: per sample it counts the `n_features` inputs plus each layer's
+    /// cached pre-activation and output, doubled for the matching gradients of the
+    /// backward pass, at 4 bytes per `f32`. Parameters and optimizer state are not
+    /// counted. Always returns at least `1`.
+    pub fn suggest_batch_size(&self, n_features: usize, mem_budget_bytes: usize) -> usize {
+        let floats: usize = n_features + self.layers.iter().map(|l| 2 * l.w.cols).sum::<usize>();
+        let per_sample = (2 * floats * std::mem::size_of::<f32>()).max(1);
+        let fit = mem_budget_bytes / per_sample;
+        if fit <= 1 { 1 } else { 1 << fit.ilog2() }
+    }
+
+    /// Returns a snapshot of each layer's `(grad_w, grad_b)`, in layer order.
+    ///
+    /// Entries are `None` for layers that have not been through a backward pass.
+    pub fn gradients(&self) -> Vec<(Option<Tensor>, Option<Tensor>)> {
+        self.layers
+            .iter()
+            .map(|l| (l.grad_w.clone(), l.grad_b.clone()))
+            .collect()
+    }
+
+    /// Removes hidden neurons, keeping only the outputs of `layer` listed in `keep`.
+    ///
+    /// The kept columns of `layer` and the matching input rows of the following layer
+    /// are retained in the order given, so the model genuinely shrinks rather than
+    /// masking. Pruning masks are sliced along with the weights. `Adam` moments for the
+    /// two layers are reset on its next step, since their sizes no longer match.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `layer` has no following layer, or if
+    /// `keep` is empty, repeats an index or names a neuron that does not exist.
+    pub fn prune_neurons(&mut self, layer: usize, keep: &[usize]) -> NeuroxResult<()> {
+        if layer + 1 >= self.layers.len() {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "layer {} has no following layer to adjust",
+                layer
+            )));
+        }
+        let width = self.layers[layer].w.cols;
+        if keep.is_empty() {
+            return Err(NeuroxError::InvalidArgument(
+                "must keep at least one neuron".into(),
+            ));
         }
+        let mut seen = vec![false; width];
+        for &j in keep {
+            if j >= width || seen[j] {
+                return Err(NeuroxError::InvalidArgument(format!(
+                    "invalid or repeated neuron index {} for a layer of {}",
+                    j, width
+                )));
+            }
+            seen[j] = true;
+        }
+        self.layers[layer].select_outputs(keep);
+        self.layers[layer + 1].select_inputs(keep);
         Ok(())
     }
 
+    /// Exports the model to an ONNX file at `path`.
+    ///
+    /// Each layer becomes a `Gemm` node followed by a `Relu`, `LeakyRelu`, `Elu`,
+    /// `Softplus`, `Sigmoid`, `Tanh` or `Softmax` node (none for `Activation::None`). The
+    /// graph input is named `input` with shape `(N, in_features)` and the output `output`
+    /// with shape `(N, out_features)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if a layer uses `Activation::GELU`, which the
+    /// targeted opset cannot express, or `NeuroxError::Io` if the file cannot be written.
+    #[cfg(feature = "onnx")]
+    pub fn export_onnx(&self, path: &str) -> NeuroxResult<()> {
+        crate::onnx::export(self, path)
+    }
+
     /// Prints a summary of the model's architecture and parameter counts.
     pub fn summary(&self) {
         println!("Model Summary:");
@@ -136,17 +1047,264 @@ impl Model {
     }
 }
 
-/// Helper function to extract a horizontal slice of a tensor's rows.
+/// Averages the class probabilities of several models on the same input.
+///
+/// Each model's logits are softmaxed, regardless of its `output_activation`, with dropout
+/// disabled.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `models` is empty, or
+/// `NeuroxError::ShapeMismatch` if the models disagree on the output width.
+pub fn ensemble_predict_proba(models: &mut [Model], x: &Tensor) -> NeuroxResult<Tensor> {
+    let (first, rest) = models
+        .split_first_mut()
+        .ok_or_else(|| NeuroxError::InvalidArgument("ensemble needs at least one model".into()))?;
+    let proba = |m: &mut Model| -> NeuroxResult<Tensor> {
+        let logits = m.with_training(false, |m| m.forward(x))?;
+        Ok(crate::activations::softmax(&logits))
+    };
+    let mut sum = proba(first)?;
+    for model in rest {
+        let probs = proba(model)?;
+        if probs.cols != sum.cols {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "ensemble members output {} and {} classes",
+                sum.cols, probs.cols
+            )));
+        }
+        for (s, p) in sum.data.iter_mut().zip(&probs.data) {
+            *s += p;
+        }
+    }
+    let n = models.len() as f32;
+    Ok(sum.map(|v| v / n))
+}
+
+/// Returns whether two models produce the same outputs on `x`, within `tol`.
+///
+/// Both models are run through `Model::forward` with dropout disabled, and every pair of
+/// output entries must differ by at most `tol`. Useful to check that a refactor or a
+/// save/load round-trip left a model's behavior unchanged. As a side effect the forward
+/// caches of both models are overwritten.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the models produce outputs of different
+/// shapes, or any error raised by either forward pass.
+pub fn compare_outputs(a: &mut Model, b: &mut Model, x: &Tensor, tol: f32) -> NeuroxResult<bool> {
+    let out_a = a.with_training(false, |m| m.forward(x))?;
+    let out_b = b.with_training(false, |m| m.forward(x))?;
+    if out_a.shape() != out_b.shape() {
+        return Err(NeuroxError::ShapeMismatch(format!(
+            "model outputs have shapes {:?} and {:?}",
+            out_a.shape(),
+            out_b.shape()
+        )));
+    }
+    Ok(out_a
+        .data
+        .iter()
+        .zip(&out_b.data)
+        .all(|(p, q)| (p - q).abs() <= tol))
+}
+
+/// Bins predictions by confidence for a reliability diagram.
 ///
-/// Creates a new tensor from rows `start` (inclusive) to `end` (exclusive).
-fn slice_rows(t: &Tensor, start: usize, end: usize) -> NeuroxResult<Tensor> {
-    assert!(start < end && end <= t.rows);
-    let cols = t.cols;
-    let mut out = Tensor::zeros(end - start, cols);
-    for i in 0..(end - start) {
-        for j in 0..cols {
-            out.set(i, j, t.get(start + i, j));
+/// Each row's confidence is its largest probability, and it counts as correct when that
+/// class is also the target's argmax. Confidences are split into `bins` equal-width bins
+/// over `[0, 1]`; for each bin the result holds `(mean_confidence, accuracy, count)`, in
+/// order of increasing confidence. Empty bins are reported as `(0.0, 0.0, 0)`. For a
+/// well-calibrated model the first two entries are close in every populated bin.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `probs` and `targets` differ in shape, or
+/// `NeuroxError::InvalidArgument` if `bins` is zero.
+pub fn reliability_curve(
+    probs: &Tensor,
+    targets: &Tensor,
+    bins: usize,
+) -> NeuroxResult<Vec<(f32, f32, usize)>> {
+    if probs.shape() != targets.shape() {
+        return Err(NeuroxError::ShapeMismatch(
+            "probs and targets must have the same shape".into(),
+        ));
+    }
+    if bins == 0 {
+        return Err(NeuroxError::InvalidArgument("bins must be positive".into()));
+    }
+    let mut conf_sum = vec![0.0f32; bins];
+    let mut correct = vec![0usize; bins];
+    let mut counts = vec![0usize; bins];
+    let predicted = probs.argmax_rows();
+    let actual = targets.argmax_rows();
+    for i in 0..probs.rows {
+        let conf = probs.get(i, predicted[i]);
+        let bin = ((conf * bins as f32) as usize).min(bins - 1);
+        conf_sum[bin] += conf;
+        counts[bin] += 1;
+        if predicted[i] == actual[i] {
+            correct[bin] += 1;
+        }
+    }
+    Ok((0..bins)
+        .map(|b| {
+            if counts[b] == 0 {
+                (0.0, 0.0, 0)
+            } else {
+                let n = counts[b] as f32;
+                (conf_sum[b] / n, correct[b] as f32 / n, counts[b])
+            }
+        })
+        .collect())
+}
+
+/// Computes the expected calibration error (ECE) of a classifier's probabilities.
+///
+/// This is the count-weighted mean of `|mean_confidence - accuracy|` over the bins of
+/// `reliability_curve`: `0.0` for perfect calibration, larger when the model is over- or
+/// underconfident. An empty `probs` yields `0.0`.
+///
+/// # Errors
+///
+/// Same as `reliability_curve`.
+pub fn expected_calibration_error(
+    probs: &Tensor,
+    targets: &Tensor,
+    bins: usize,
+) -> NeuroxResult<f32> {
+    let curve = reliability_curve(probs, targets, bins)?;
+    if probs.rows == 0 {
+        return Ok(0.0);
+    }
+    let n = probs.rows as f32;
+    Ok(curve
+        .iter()
+        .map(|&(conf, acc, count)| count as f32 / n * (conf - acc).abs())
+        .sum())
+}
+
+/// Computes the area under the ROC curve of binary `scores` against `labels` (0 or 1).
+///
+/// Uses the rank-based Mann-Whitney formula: the AUC is the probability that a random
+/// positive scores higher than a random negative, with tied scores sharing their average
+/// rank (so counting as half). `1.0` means perfect separation, `0.5` chance level.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `scores` and `labels` differ in length, or
+/// `NeuroxError::InvalidArgument` if a label is not 0 or 1, or only one class is present.
+pub fn roc_auc(scores: &[f32], labels: &[usize]) -> NeuroxResult<f32> {
+    if scores.len() != labels.len() {
+        return Err(NeuroxError::ShapeMismatch(format!(
+            "{} scores but {} labels",
+            scores.len(),
+            labels.len()
+        )));
+    }
+    if labels.iter().any(|&l| l > 1) {
+        return Err(NeuroxError::InvalidArgument(
+            "roc_auc labels must be 0 or 1".into(),
+        ));
+    }
+    let n_pos = labels.iter().filter(|&&l| l == 1).count();
+    let n_neg = labels.len() - n_pos;
+    if n_pos == 0 || n_neg == 0 {
+        return Err(NeuroxError::InvalidArgument(
+            "roc_auc needs both positive and negative samples".into(),
+        ));
+    }
+
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]));
+    let mut pos_rank_sum = 0.0f64;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && scores[order[end]] == scores[order[start]] {
+            end += 1;
         }
+        // 1-based ranks start + 1 ..= end share their mean.
+        let rank = (start + end + 1) as f64 / 2.0;
+        let tied_pos = order[start..end]
+            .iter()
+            .filter(|&&i| labels[i] == 1)
+            .count();
+        pos_rank_sum += rank * tied_pos as f64;
+        start = end;
+    }
+    let u = pos_rank_sum - (n_pos * (n_pos + 1)) as f64 / 2.0;
+    Ok((u / (n_pos as f64 * n_neg as f64)) as f32)
+}
+
+/// Computes the one-vs-rest ROC-AUC of every class.
+///
+/// Class `c` is scored by column `c` of `probs`, and a row counts as positive when `c` is
+/// the argmax of its `targets` row. Returns one AUC per column.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `probs` and `targets` differ in shape, or
+/// `NeuroxError::InvalidArgument` if some class has no positive or no negative rows.
+pub fn roc_auc_ovr(probs: &Tensor, targets: &Tensor) -> NeuroxResult<Vec<f32>> {
+    if probs.shape() != targets.shape() {
+        return Err(NeuroxError::ShapeMismatch(
+            "probs and targets must have the same shape".into(),
+        ));
+    }
+    let actual = targets.argmax_rows();
+    (0..probs.cols)
+        .map(|c| {
+            let scores: Vec<f32> = (0..probs.rows).map(|i| probs.get(i, c)).collect();
+            let labels: Vec<usize> = actual.iter().map(|&a| usize::from(a == c)).collect();
+            roc_auc(&scores, &labels)
+        })
+        .collect()
+}
+
+/// Accumulates a confusion matrix across batches of predictions.
+///
+/// Rows index the true class and columns the predicted class.
+#[derive(Clone, Debug)]
+pub struct ConfusionAccumulator {
+    num_classes: usize,
+    counts: Vec<usize>,
+}
+
+impl ConfusionAccumulator {
+    /// Creates an empty accumulator for `num_classes` classes.
+    pub fn new(num_classes: usize) -> Self {
+        Self {
+            num_classes,
+            counts: vec![0; num_classes * num_classes],
+        }
+    }
+
+    /// Adds a batch of predicted and true class indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `preds` and `targets` differ in length or contain an index
+    /// outside `0..num_classes`.
+    pub fn update(&mut self, preds: &[usize], targets: &[usize]) {
+        assert_eq!(
+            preds.len(),
+            targets.len(),
+            "preds and targets must have the same length"
+        );
+        for (&p, &t) in preds.iter().zip(targets) {
+            assert!(
+                p < self.num_classes && t < self.num_classes,
+                "class index out of range"
+            );
+            self.counts[t * self.num_classes + p] += 1;
+        }
+    }
+
+    /// Returns the accumulated `(num_classes, num_classes)` matrix of counts.
+    pub fn matrix(&self) -> Tensor {
+        let data = self.counts.iter().map(|&c| c as f32).collect();
+        Tensor::from_data(data, self.num_classes, self.num_classes)
     }
-    Ok(out)
 }
@@ -0,0 +1,191 @@
+//! Minimal ONNX export for sequential `Dense` models.
+//!
+//! The protobuf wire format is written by hand to avoid pulling in a protobuf
+//! dependency; only the handful of `onnx.proto` messages needed for a
+//! Gemm + activation graph are emitted.
+
+use crate::errors::{NeuroxError, NeuroxResult};
+use crate::layers::Activation;
+use crate::model::Model;
+use crate::tensor::Tensor;
+use std::fs;
+
+/// IR version written to the model (ONNX 1.13+).
+const IR_VERSION: i64 = 8;
+/// Default-domain opset the graph targets.
+const OPSET_VERSION: i64 = 13;
+/// `TensorProto.DataType.FLOAT`.
+const FLOAT: i64 = 1;
+/// `AttributeProto.AttributeType.FLOAT`.
+const ATTR_FLOAT: i64 = 1;
+
+/// Serializes `model` as an ONNX `ModelProto` and writes it to `path`.
+pub(crate) fn export(model: &Model, path: &str) -> NeuroxResult<()> {
+    let mut graph = Vec::new();
+    let mut current = String::from("input");
+    let last = model.layers.len().saturating_sub(1);
+
+    for (i, layer) in model.layers.iter().enumerate() {
+        let w_name = format!("W{}", i);
+        let b_name = format!("B{}", i);
+        let (op, alpha) = match layer.activation {
+            Activation::ReLU => (Some("Relu"), None),
+            Activation::LeakyReLU(alpha) => (Some("LeakyRelu"), Some(alpha)),
+            Activation::ELU(alpha) => (Some("Elu"), Some(alpha)),
+            Activation::Sigmoid => (Some("Sigmoid"), None),
+            Activation::Tanh => (Some("Tanh"), None),
+            Activation::Softplus => (Some("Softplus"), None),
+            Activation::Softmax => (Some("Softmax"), None),
+            Activation::None => (None, None),
+            Activation::GELU => {
+                return Err(NeuroxError::InvalidArgument(format!(
+                    "layer {} uses GELU, which opset {} has no operator for",
+                    i, OPSET_VERSION
+                )));
+            }
+        };
+
+        let gemm_out = if i == last && op.is_none() {
+            String::from("output")
+        } else {
+            format!("gemm{}", i)
+        };
+        let gemm = node(
+            &format!("Gemm_{}", i),
+            "Gemm",
+            &[&current, &w_name, &b_name],
+            &gemm_out,
+            None,
+        );
+        message(&mut graph, 1, &gemm);
+        current = gemm_out;
+
+        if let Some(op) = op {
+            let act_out = if i == last {
+                String::from("output")
+            } else {
+                format!("act{}", i)
+            };
+            let alpha = alpha.map(|a| ("alpha", a));
+            let act = node(&format!("{}_{}", op, i), op, &[&current], &act_out, alpha);
+            message(&mut graph, 1, &act);
+            current = act_out;
+        }
+    }
+
+    string(&mut graph, 2, "neurox");
+    for (i, layer) in model.layers.iter().enumerate() {
+        message(&mut graph, 5, &initializer(&format!("W{}", i), &layer.w));
+        message(&mut graph, 5, &initializer(&format!("B{}", i), &layer.b));
+    }
+    let in_features = model.layers.first().map_or(0, |l| l.w.rows);
+    let out_features = model.layers.last().map_or(0, |l| l.w.cols);
+    message(&mut graph, 11, &value_info("input", in_features));
+    message(&mut graph, 12, &value_info("output", out_features));
+
+    let mut opset = Vec::new();
+    string(&mut opset, 1, "");
+    int(&mut opset, 2, OPSET_VERSION);
+
+    let mut out = Vec::new();
+    int(&mut out, 1, IR_VERSION);
+    string(&mut out, 2, "neurox");
+    string(&mut out, 3, env!("CARGO_PKG_VERSION"));
+    message(&mut out, 7, &graph);
+    message(&mut out, 8, &opset);
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Encodes a `NodeProto`, with an optional float attribute such as `LeakyRelu`'s alpha.
+fn node(
+    name: &str,
+    op_type: &str,
+    inputs: &[&str],
+    output: &str,
+    attribute: Option<(&str, f32)>,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for input in inputs {
+        string(&mut buf, 1, input);
+    }
+    string(&mut buf, 2, output);
+    string(&mut buf, 3, name);
+    string(&mut buf, 4, op_type);
+    if let Some((attr_name, value)) = attribute {
+        let mut attr = Vec::new();
+        string(&mut attr, 1, attr_name);
+        float(&mut attr, 2, value);
+        int(&mut attr, 20, ATTR_FLOAT);
+        message(&mut buf, 5, &attr);
+    }
+    buf
+}
+
+/// Encodes a float `TensorProto` holding `t` in row-major order.
+fn initializer(name: &str, t: &Tensor) -> Vec<u8> {
+    let mut buf = Vec::new();
+    int(&mut buf, 1, t.rows as i64);
+    int(&mut buf, 1, t.cols as i64);
+    int(&mut buf, 2, FLOAT);
+    string(&mut buf, 8, name);
+    let raw: Vec<u8> = t.data.iter().flat_map(|v| v.to_le_bytes()).collect();
+    message(&mut buf, 9, &raw);
+    buf
+}
+
+/// Encodes a `ValueInfoProto` for a float tensor of shape `(N, features)`.
+fn value_info(name: &str, features: usize) -> Vec<u8> {
+    let mut batch = Vec::new();
+    string(&mut batch, 2, "N");
+    let mut width = Vec::new();
+    int(&mut width, 1, features as i64);
+
+    let mut shape = Vec::new();
+    message(&mut shape, 1, &batch);
+    message(&mut shape, 1, &width);
+
+    let mut tensor_type = Vec::new();
+    int(&mut tensor_type, 1, FLOAT);
+    message(&mut tensor_type, 2, &shape);
+
+    let mut type_proto = Vec::new();
+    message(&mut type_proto, 1, &tensor_type);
+
+    let mut buf = Vec::new();
+    string(&mut buf, 1, name);
+    message(&mut buf, 2, &type_proto);
+    buf
+}
+
+fn varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Writes a varint (wire type 0) field.
+fn int(buf: &mut Vec<u8>, field: u64, v: i64) {
+    varint(buf, field << 3);
+    varint(buf, v as u64);
+}
+
+/// Writes a 32-bit (wire type 5) float field.
+fn float(buf: &mut Vec<u8>, field: u64, v: f32) {
+    varint(buf, (field << 3) | 5);
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+/// Writes a length-delimited (wire type 2) field.
+fn message(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(buf, (field << 3) | 2);
+    varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn string(buf: &mut Vec<u8>, field: u64, s: &str) {
+    message(buf, field, s.as_bytes());
+}
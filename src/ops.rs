@@ -1,5 +1,7 @@
 //! Provides basic mathematical operations for `Tensor`s.
 
+#[cfg(feature = "parallel")]
+use crate::utils;
 use crate::{errors::NeuroxError, errors::NeuroxResult, tensor::Tensor};
 
 /// Performs matrix multiplication on two tensors, `a` and `b`.
@@ -7,6 +9,10 @@ use crate::{errors::NeuroxError, errors::NeuroxResult, tensor::Tensor};
 /// Calculates $C = A \times B$, where `a` has shape `(m, k)` and `b` has shape `(k, n)`.
 /// The resulting tensor `C` will have shape `(m, n)`.
 ///
+/// With the `parallel` feature, output rows are computed on the rayon thread pool.
+/// Results match serial builds bit for bit unless `utils::set_deterministic(false)` is
+/// called.
+///
 /// # Errors
 ///
 /// Returns `NeuroxError::ShapeMismatch` if `a.cols` is not equal to `b.rows`.
@@ -16,35 +22,173 @@ pub fn matmul(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
             "a.cols must equal b.rows for matmul".into(),
         ));
     }
-    let m = a.rows;
-    let k = a.cols;
-    let n = b.cols;
+    matmul_flags(a, b, false, false)
+}
+
+/// Matrix multiplication with either operand read as transposed, without copying it.
+///
+/// Computes `op(a) x op(b)` where `op(t)` is `tᵀ` if the corresponding flag is set and
+/// `t` otherwise; e.g. `matmul_flags(x, dz, true, false)` is `xᵀ · dz`. Parallelism and
+/// determinism follow `matmul`.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the inner dimensions of `op(a)` and `op(b)`
+/// differ.
+pub fn matmul_flags(a: &Tensor, b: &Tensor, trans_a: bool, trans_b: bool) -> NeuroxResult<Tensor> {
+    let (m, k) = if trans_a {
+        (a.cols, a.rows)
+    } else {
+        (a.rows, a.cols)
+    };
+    let (kb, n) = if trans_b {
+        (b.cols, b.rows)
+    } else {
+        (b.rows, b.cols)
+    };
+    if k != kb {
+        return Err(NeuroxError::ShapeMismatch(format!(
+            "inner dimensions {} and {} differ in matmul_flags",
+            k, kb
+        )));
+    }
+    let a_at = |i: usize, t: usize| if trans_a { a.get(t, i) } else { a.get(i, t) };
+    let b_at = |t: usize, j: usize| if trans_b { b.get(j, t) } else { b.get(t, j) };
+    // Accumulates in index order, which is what keeps results reproducible.
+    let dot = |i: usize, j: usize| {
+        let mut s = 0.0;
+        for t in 0..k {
+            s += a_at(i, t) * b_at(t, j);
+        }
+        s
+    };
+
     let mut out = Tensor::zeros(m, n);
-    for i in 0..m {
-        for j in 0..n {
-            let mut s = 0.0;
+    if n == 0 {
+        return Ok(out);
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        // Independent partial sums shorten the add dependency chain of long dot products.
+        const LANES: usize = 8;
+        let dot_lanes = |i: usize, j: usize| {
+            let mut acc = [0.0f32; LANES];
             for t in 0..k {
-                s += a.get(i, t) * b.get(t, j);
+                acc[t % LANES] += a_at(i, t) * b_at(t, j);
             }
-            out.set(i, j, s);
+            acc.iter().sum::<f32>()
+        };
+        let deterministic = utils::is_deterministic();
+        out.data.par_chunks_mut(n).enumerate().for_each(|(i, row)| {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = if deterministic {
+                    dot(i, j)
+                } else {
+                    dot_lanes(i, j)
+                };
+            }
+        });
+    }
+    #[cfg(not(feature = "parallel"))]
+    for (i, row) in out.data.chunks_mut(n).enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = dot(i, j);
         }
     }
     Ok(out)
 }
 
-/// Performs element-wise addition of two tensors.
+/// Applies a binary operation element-wise with NumPy-style broadcasting.
+///
+/// Along each axis the sizes must be equal or one of them must be `1`, in which case
+/// that operand is repeated along the axis. A `(1, 1)` tensor therefore acts as a scalar,
+/// a `(1, n)` tensor as a row vector and a `(m, 1)` tensor as a column vector.
 ///
 /// # Errors
 ///
-/// Returns `NeuroxError::ShapeMismatch` if `a` and `b` do not have identical shapes.
-pub fn add(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
-    if a.rows != b.rows || a.cols != b.cols {
-        return Err(NeuroxError::ShapeMismatch(
-            "tensors must have the same shape for element-wise add".into(),
-        ));
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn broadcast_binary(a: &Tensor, b: &Tensor, op: fn(f32, f32) -> f32) -> NeuroxResult<Tensor> {
+    let rows = broadcast_dim(a.rows, b.rows)?;
+    let cols = broadcast_dim(a.cols, b.cols)?;
+    let mut out = Tensor::zeros(rows, cols);
+    for i in 0..rows {
+        let (ai, bi) = (i % a.rows, i % b.rows);
+        for j in 0..cols {
+            let (aj, bj) = (j % a.cols, j % b.cols);
+            out.set(i, j, op(a.get(ai, aj), b.get(bi, bj)));
+        }
     }
-    let data = a.data.iter().zip(&b.data).map(|(x, y)| x + y).collect();
-    Ok(Tensor::from_data(data, a.rows, a.cols))
+    Ok(out)
+}
+
+/// Resolves the broadcast size of a single axis.
+fn broadcast_dim(a: usize, b: usize) -> NeuroxResult<usize> {
+    if a == b || b == 1 {
+        Ok(a)
+    } else if a == 1 {
+        Ok(b)
+    } else {
+        Err(NeuroxError::ShapeMismatch(format!(
+            "cannot broadcast dimensions {} and {}",
+            a, b
+        )))
+    }
+}
+
+/// Performs element-wise addition of two tensors, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn add(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, |x, y| x + y)
+}
+
+/// Performs element-wise subtraction `a - b`, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn sub(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, |x, y| x - y)
+}
+
+/// Performs element-wise multiplication, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn mul(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, |x, y| x * y)
+}
+
+/// Performs element-wise division `a / b`, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn div(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, |x, y| x / y)
+}
+
+/// Computes the element-wise maximum, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn maximum(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, f32::max)
+}
+
+/// Computes the element-wise minimum, with broadcasting.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if the shapes cannot be broadcast together.
+pub fn minimum(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    broadcast_binary(a, b, f32::min)
 }
 
 /// Performs element-wise multiplication of two tensors.
@@ -61,3 +205,277 @@ pub fn mul_elementwise(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
     let data = a.data.iter().zip(&b.data).map(|(x, y)| x * y).collect();
     Ok(Tensor::from_data(data, a.rows, a.cols))
 }
+
+/// Selects element-wise between two tensors: `a[i]` where `cond[i] != 0`, else `b[i]`.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `cond`, `a` and `b` do not share a shape.
+pub fn where_mask(cond: &Tensor, a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    if cond.shape() != a.shape() || cond.shape() != b.shape() {
+        return Err(NeuroxError::ShapeMismatch(
+            "cond, a and b must have the same shape for where_mask".into(),
+        ));
+    }
+    let data = cond
+        .data
+        .iter()
+        .zip(a.data.iter().zip(&b.data))
+        .map(|(&c, (&x, &y))| if c != 0.0 { x } else { y })
+        .collect();
+    Ok(Tensor::from_data(data, a.rows, a.cols))
+}
+
+/// Stacks same-shape tensors into a batch, flattening each `(r, c)` input into one row.
+///
+/// The result has shape `(tensors.len(), r * c)`, with row `i` holding `tensors[i]`
+/// in row-major order.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `tensors` is empty, or
+/// `NeuroxError::ShapeMismatch` if the inputs do not all share a shape.
+pub fn stack_flatten(tensors: &[Tensor]) -> NeuroxResult<Tensor> {
+    let first = tensors
+        .first()
+        .ok_or_else(|| NeuroxError::InvalidArgument("cannot stack zero tensors".into()))?;
+    let width = first.data.len();
+    let mut data = Vec::with_capacity(tensors.len() * width);
+    for t in tensors {
+        if t.shape() != first.shape() {
+            return Err(NeuroxError::ShapeMismatch(
+                "all tensors must have the same shape to stack".into(),
+            ));
+        }
+        data.extend_from_slice(&t.data);
+    }
+    Ok(Tensor::from_data(data, tensors.len(), width))
+}
+
+/// Concatenates tensors with the same number of columns along the row axis.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `tensors` is empty, or
+/// `NeuroxError::ShapeMismatch` if the inputs differ in column count.
+pub fn vstack(tensors: &[Tensor]) -> NeuroxResult<Tensor> {
+    let first = tensors
+        .first()
+        .ok_or_else(|| NeuroxError::InvalidArgument("cannot stack zero tensors".into()))?;
+    let rows = tensors.iter().map(|t| t.rows).sum();
+    let mut data = Vec::with_capacity(rows * first.cols);
+    for t in tensors {
+        if t.cols != first.cols {
+            return Err(NeuroxError::ShapeMismatch(format!(
+                "cannot vstack tensors with {} and {} columns",
+                first.cols, t.cols
+            )));
+        }
+        data.extend_from_slice(&t.data);
+    }
+    Ok(Tensor::from_data(data, rows, first.cols))
+}
+
+/// Applies 1D max pooling to each row, treating the row as a sequence.
+///
+/// Output row `i` has `(cols - window) / stride + 1` entries; trailing elements that do
+/// not fill a complete window are dropped. Ties resolve to the earliest position.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `window` or `stride` is zero or `window`
+/// exceeds the row length.
+pub fn max_pool1d(x: &Tensor, window: usize, stride: usize) -> NeuroxResult<Tensor> {
+    let out_cols = pool_output_len(x.cols, window, stride)?;
+    let mut out = Tensor::zeros(x.rows, out_cols);
+    for i in 0..x.rows {
+        for o in 0..out_cols {
+            let arg = pool_argmax(x, i, o * stride, window);
+            out.set(i, o, x.get(i, arg));
+        }
+    }
+    Ok(out)
+}
+
+/// Routes the gradient of `max_pool1d` back to the input positions that won each window.
+///
+/// `grad_out` must have the shape produced by `max_pool1d(x, window, stride)`. Positions
+/// selected by several overlapping windows accumulate their gradients.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` for invalid pooling parameters, or
+/// `NeuroxError::ShapeMismatch` if `grad_out` has the wrong shape.
+pub fn max_pool1d_grad(
+    x: &Tensor,
+    grad_out: &Tensor,
+    window: usize,
+    stride: usize,
+) -> NeuroxResult<Tensor> {
+    let out_cols = pool_output_len(x.cols, window, stride)?;
+    if grad_out.shape() != (x.rows, out_cols) {
+        return Err(NeuroxError::ShapeMismatch(
+            "grad_out must match the pooled output shape".into(),
+        ));
+    }
+    let mut grad = x.zeros_like();
+    for i in 0..x.rows {
+        for o in 0..out_cols {
+            let arg = pool_argmax(x, i, o * stride, window);
+            grad.set(i, arg, grad.get(i, arg) + grad_out.get(i, o));
+        }
+    }
+    Ok(grad)
+}
+
+/// Validates pooling parameters and returns the pooled row length.
+fn pool_output_len(len: usize, window: usize, stride: usize) -> NeuroxResult<usize> {
+    if window == 0 || stride == 0 {
+        return Err(NeuroxError::InvalidArgument(
+            "window and stride must be positive".into(),
+        ));
+    }
+    if window > len {
+        return Err(NeuroxError::InvalidArgument(
+            "window must not exceed the row length".into(),
+        ));
+    }
+    Ok((len - window) / stride + 1)
+}
+
+/// Column of the first maximum in row `i` over `start..start + window`.
+fn pool_argmax(x: &Tensor, i: usize, start: usize, window: usize) -> usize {
+    let mut best = start;
+    for j in start + 1..start + window {
+        if x.get(i, j) > x.get(i, best) {
+            best = j;
+        }
+    }
+    best
+}
+
+/// Inverts a square matrix using Gauss-Jordan elimination with partial pivoting.
+///
+/// Elimination runs in `f64` to limit round-off on small systems.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `a` is not square, or
+/// `NeuroxError::InvalidArgument` if it is (numerically) singular.
+pub fn inverse(a: &Tensor) -> NeuroxResult<Tensor> {
+    if a.rows != a.cols {
+        return Err(NeuroxError::ShapeMismatch(
+            "inverse requires a square matrix".into(),
+        ));
+    }
+    let n = a.rows;
+    let w = 2 * n;
+    // Augmented matrix [A | I].
+    let mut m = vec![0.0f64; n * w];
+    for i in 0..n {
+        for j in 0..n {
+            m[i * w + j] = a.get(i, j) as f64;
+        }
+        m[i * w + n + i] = 1.0;
+    }
+    let max_abs = a
+        .data
+        .iter()
+        .fold(0.0f64, |acc, &v| acc.max((v as f64).abs()));
+    let tol = f64::EPSILON * n as f64 * max_abs.max(1.0);
+
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|&r1, &r2| m[r1 * w + col].abs().total_cmp(&m[r2 * w + col].abs()))
+            .unwrap();
+        if m[pivot_row * w + col].abs() <= tol {
+            return Err(NeuroxError::InvalidArgument("matrix is singular".into()));
+        }
+        if pivot_row != col {
+            for j in 0..w {
+                m.swap(col * w + j, pivot_row * w + j);
+            }
+        }
+        let pivot = m[col * w + col];
+        for j in 0..w {
+            m[col * w + j] /= pivot;
+        }
+        for r in 0..n {
+            if r == col {
+                continue;
+            }
+            let factor = m[r * w + col];
+            if factor != 0.0 {
+                for j in 0..w {
+                    m[r * w + j] -= factor * m[col * w + j];
+                }
+            }
+        }
+    }
+
+    let mut out = Tensor::zeros(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            out.set(i, j, m[i * w + n + j] as f32);
+        }
+    }
+    Ok(out)
+}
+
+/// Solves the least-squares problem `min ||A x - b||` via the normal equations.
+///
+/// Computes `x = (AᵀA)⁻¹ Aᵀ b` for `a` of shape `(n, k)` and `b` of shape `(n, m)`,
+/// returning a `(k, m)` solution.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::ShapeMismatch` if `a.rows != b.rows`, or
+/// `NeuroxError::InvalidArgument` if `AᵀA` is singular (e.g. collinear columns).
+pub fn lstsq(a: &Tensor, b: &Tensor) -> NeuroxResult<Tensor> {
+    if a.rows != b.rows {
+        return Err(NeuroxError::ShapeMismatch(
+            "a and b must have the same number of rows for lstsq".into(),
+        ));
+    }
+    let at = a.transpose();
+    let ata_inv = inverse(&matmul(&at, a)?)?;
+    matmul(&ata_inv, &matmul(&at, b)?)
+}
+
+/// Computes `ln(sum(exp(x)))` along an axis without overflow.
+///
+/// Uses `max + ln(sum(exp(x - max)))`. `axis = 1` reduces each row to a `(rows, 1)`
+/// result and `axis = 0` reduces each column to a `(1, cols)` result.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `axis` is not `0` or `1`.
+pub fn logsumexp(x: &Tensor, axis: usize) -> NeuroxResult<Tensor> {
+    match axis {
+        0 => {
+            let data = (0..x.cols)
+                .map(|j| stable_lse(&(0..x.rows).map(|i| x.get(i, j)).collect::<Vec<_>>()))
+                .collect();
+            Ok(Tensor::from_data(data, 1, x.cols))
+        }
+        1 => {
+            let data = (0..x.rows)
+                .map(|i| stable_lse(&x.data[i * x.cols..(i + 1) * x.cols]))
+                .collect();
+            Ok(Tensor::from_data(data, x.rows, 1))
+        }
+        _ => Err(NeuroxError::InvalidArgument(format!(
+            "logsumexp axis must be 0 or 1, got {}",
+            axis
+        ))),
+    }
+}
+
+/// Log-sum-exp of a slice with max subtraction; `-inf` for an empty or all `-inf` slice.
+fn stable_lse(values: &[f32]) -> f32 {
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max == f32::NEG_INFINITY {
+        return f32::NEG_INFINITY;
+    }
+    max + values.iter().map(|v| (v - max).exp()).sum::<f32>().ln()
+}
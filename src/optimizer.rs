@@ -1,28 +1,214 @@
 //! Provides optimization algorithms for updating model parameters.
 
+use crate::constants;
+use crate::errors::{NeuroxError, NeuroxResult};
 use crate::layers::Dense;
+use std::fs;
 
-/// A simple Stochastic Gradient Descent (SGD) optimizer.
+/// Leading bytes of a file written by `Adam::save_state`.
+const ADAM_MAGIC: &[u8; 8] = b"NXADAM01";
+
+/// Common interface of the optimizers, used by `Model::train`.
+pub trait Optimizer {
+    /// Updates the parameters of `layers` from their stored gradients.
+    fn step(&mut self, layers: &mut [Dense]);
+
+    /// Returns the current learning rate.
+    fn lr(&self) -> f32;
+
+    /// Sets the learning rate used by subsequent steps.
+    fn set_lr(&mut self, lr: f32);
+}
+
+/// A learning-rate schedule, mapping a step (usually an epoch) to a learning rate.
 ///
-/// This implementation does not include momentum for simplicity.
+/// Any `Fn(usize) -> f32` closure is a schedule.
+pub trait LrSchedule {
+    /// Returns the learning rate for `step`.
+    fn lr_at(&self, step: usize) -> f32;
+}
+
+impl<F> LrSchedule for F
+where
+    F: Fn(usize) -> f32,
+{
+    fn lr_at(&self, step: usize) -> f32 {
+        self(step)
+    }
+}
+
+/// The one-cycle learning-rate policy.
+///
+/// Over the first `warmup_frac` of `total_steps` the learning rate ramps linearly from
+/// `max_lr / div_factor` up to `max_lr`, then follows a cosine curve down to
+/// `max_lr / (div_factor * final_div_factor)` at the last step, where it stays. With the
+/// defaults the cycle starts at `max_lr / 25` and ends four orders of magnitude lower.
+///
+/// `momentum_at` provides the matching inverse momentum schedule, which falls from
+/// `max_momentum` to `base_momentum` while the learning rate rises and climbs back
+/// afterwards; apply it to `SGD::momentum` between steps if desired.
+pub struct OneCycle {
+    pub total_steps: usize,
+    pub max_lr: f32,
+    pub warmup_frac: f32,
+    pub div_factor: f32,
+    pub final_div_factor: f32,
+    pub base_momentum: f32,
+    pub max_momentum: f32,
+}
+
+impl OneCycle {
+    /// Creates a one-cycle schedule peaking at `max_lr` after `warmup_frac` of
+    /// `total_steps`, with `div_factor = 25`, `final_div_factor = 1e4` and momentum
+    /// cycling between 0.85 and 0.95.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `warmup_frac` is not in `[0, 1]`.
+    pub fn new(total_steps: usize, max_lr: f32, warmup_frac: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&warmup_frac),
+            "warmup_frac must be in [0, 1]"
+        );
+        Self {
+            total_steps,
+            max_lr,
+            warmup_frac,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+            base_momentum: 0.85,
+            max_momentum: 0.95,
+        }
+    }
+
+    /// Returns the momentum for `step`, moving opposite to the learning rate.
+    pub fn momentum_at(&self, step: usize) -> f32 {
+        let (t, _) = self.position(step);
+        self.max_momentum - (self.max_momentum - self.base_momentum) * t
+    }
+
+    /// Maps `step` to its position in the cycle, from 0 at either end to 1 at the peak,
+    /// and whether it lies in the rising (warmup) phase.
+    fn position(&self, step: usize) -> (f32, bool) {
+        let last = self.total_steps.saturating_sub(1);
+        let peak = (last as f32 * self.warmup_frac).round() as usize;
+        let step = step.min(last);
+        if step < peak {
+            (step as f32 / peak as f32, true)
+        } else if peak == last {
+            (1.0, false)
+        } else {
+            let progress = (step - peak) as f32 / (last - peak) as f32;
+            (0.5 * (1.0 + (std::f32::consts::PI * progress).cos()), false)
+        }
+    }
+}
+
+impl LrSchedule for OneCycle {
+    fn lr_at(&self, step: usize) -> f32 {
+        let initial = self.max_lr / self.div_factor;
+        let (t, rising) = self.position(step);
+        // The annealing phase ends well below the starting value.
+        let floor = if rising {
+            initial
+        } else {
+            initial / self.final_div_factor
+        };
+        floor + (self.max_lr - floor) * t
+    }
+}
+
+/// A Stochastic Gradient Descent (SGD) optimizer with optional momentum.
+///
+/// With `momentum = 0` (the default) each step is `param -= lr * grad`. Otherwise a
+/// velocity `v = momentum * v + grad` is kept per parameter and the update is
+/// `param -= lr * v`, or with `nesterov` set, the reformulated Nesterov step
+/// `param -= lr * (grad + momentum * v)`. The latter evaluates the momentum lookahead
+/// using the current gradient instead of recomputing it at the lookahead point, as most
+/// frameworks do.
 pub struct SGD {
     pub lr: f32,
+    pub momentum: f32,
+    pub nesterov: bool,
+    // Per-layer velocities for weights and biases, sized on the first step.
+    v_w: Vec<Vec<f32>>,
+    v_b: Vec<Vec<f32>>,
 }
 
 impl SGD {
-    /// Creates a new SGD optimizer with a given learning rate.
+    /// Creates a new SGD optimizer with a given learning rate and no momentum.
     pub fn new(lr: f32) -> Self {
-        Self { lr }
+        Self::with_momentum(lr, 0.0, false)
+    }
+
+    /// Creates an SGD optimizer with heavy-ball (or, if `nesterov`, Nesterov) momentum.
+    pub fn with_momentum(lr: f32, momentum: f32, nesterov: bool) -> Self {
+        Self {
+            lr,
+            momentum,
+            nesterov,
+            v_w: Vec::new(),
+            v_b: Vec::new(),
+        }
     }
 
     /// Performs a single optimization step, updating the parameters of all layers.
-    pub fn step(&self, layers: &mut [Dense]) {
-        for l in layers {
-            l.apply_gradients(self.lr);
+    ///
+    /// Takes `&mut self` because the momentum velocities are updated in place.
+    pub fn step(&mut self, layers: &mut [Dense]) {
+        if self.momentum == 0.0 {
+            for l in layers {
+                l.apply_gradients(self.lr);
+            }
+            return;
+        }
+        self.v_w.resize_with(layers.len(), Vec::new);
+        self.v_b.resize_with(layers.len(), Vec::new);
+        for (li, l) in layers.iter_mut().enumerate() {
+            if let Some(gw) = &l.grad_w {
+                self.update(&mut l.w.data, &gw.data, li, false);
+            }
+            if let Some(gb) = &l.grad_b {
+                self.update(&mut l.b.data, &gb.data, li, true);
+            }
+            l.apply_mask();
+        }
+    }
+
+    /// Applies the momentum update to one parameter tensor, resetting its velocity if
+    /// the parameter was resized.
+    fn update(&mut self, params: &mut [f32], grads: &[f32], li: usize, bias: bool) {
+        let (momentum, nesterov, lr) = (self.momentum, self.nesterov, self.lr);
+        let velocity = if bias {
+            &mut self.v_b[li]
+        } else {
+            &mut self.v_w[li]
+        };
+        if velocity.len() != params.len() {
+            *velocity = vec![0.0; params.len()];
+        }
+        for ((p, &g), v) in params.iter_mut().zip(grads).zip(velocity.iter_mut()) {
+            *v = momentum * *v + g;
+            let step = if nesterov { g + momentum * *v } else { *v };
+            *p -= lr * step;
         }
     }
 }
 
+impl Optimizer for SGD {
+    fn step(&mut self, layers: &mut [Dense]) {
+        SGD::step(self, layers)
+    }
+
+    fn lr(&self) -> f32 {
+        self.lr
+    }
+
+    fn set_lr(&mut self, lr: f32) {
+        self.lr = lr;
+    }
+}
+
 /// The Adam optimization algorithm.
 ///
 /// Adam maintains per-parameter adaptive learning rates from estimates of
@@ -63,7 +249,7 @@ impl Adam {
             lr,
             beta1: 0.9,
             beta2: 0.999,
-            eps: 1e-8,
+            eps: constants::ADAM_EPS,
             t: 0,
             m_w,
             v_w,
@@ -73,7 +259,19 @@ impl Adam {
     }
 
     /// Performs a single Adam optimization step.
+    ///
+    /// Moment buffers are resized to match `layers` first, so layers added or reshaped
+    /// after construction start with fresh (zero) state instead of stale buffers.
+    ///
+    /// `t` advances once per call, so when accumulating gradients over `N` micro-batches
+    /// call `step` once after the last one. The stored `grad_w`/`grad_b` should then hold
+    /// the *average* of the micro-batch gradients rather than their sum: the built-in
+    /// losses already average over the batch, so with equal-sized micro-batches the
+    /// average equals the full-batch gradient and the moments match a single full-batch
+    /// step exactly. Summing scales `m` by `N` and `v` by `N²`; the update direction is
+    /// nearly unchanged but `eps` and any gradient clipping see the inflated magnitude.
     pub fn step(&mut self, layers: &mut [Dense]) {
+        self.ensure_state(layers);
         self.t += 1;
         for (li, l) in layers.iter_mut().enumerate() {
             if l.grad_w.is_none() || l.grad_b.is_none() {
@@ -109,6 +307,216 @@ impl Adam {
                 let v_hat = (*v) / (1.0 - self.beta2.powi(self.t as i32));
                 l.b.data[i] -= self.lr * m_hat / (v_hat.sqrt() + self.eps);
             }
+
+            l.apply_mask();
+        }
+    }
+
+    /// Writes the hyperparameters, timestep and moment buffers to `path`.
+    ///
+    /// Together with the model weights this is enough to resume training with the exact
+    /// updates an uninterrupted run would have made. The format is a small little-endian
+    /// binary layout read back by `load_state`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::Io` if the file cannot be written.
+    pub fn save_state(&self, path: &str) -> NeuroxResult<()> {
+        let mut out = Vec::new();
+        out.extend_from_slice(ADAM_MAGIC);
+        for v in [self.lr, self.beta1, self.beta2, self.eps] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out.extend_from_slice(&(self.t as u64).to_le_bytes());
+        out.extend_from_slice(&(self.m_w.len() as u64).to_le_bytes());
+        for li in 0..self.m_w.len() {
+            for buf in [&self.m_w[li], &self.v_w[li], &self.m_b[li], &self.v_b[li]] {
+                out.extend_from_slice(&(buf.len() as u64).to_le_bytes());
+                for v in buf {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+            }
         }
+        fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Reads an optimizer written by `save_state`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::Io` if the file cannot be read, or
+    /// `NeuroxError::InvalidArgument` if it is not a valid Adam state file.
+    pub fn load_state(path: &str) -> NeuroxResult<Adam> {
+        let bytes = fs::read(path)?;
+        if !bytes.starts_with(ADAM_MAGIC) {
+            return Err(NeuroxError::InvalidArgument(
+                "not an Adam state file".into(),
+            ));
+        }
+        let mut r = StateReader {
+            bytes: &bytes[ADAM_MAGIC.len()..],
+        };
+        let lr = r.f32()?;
+        let beta1 = r.f32()?;
+        let beta2 = r.f32()?;
+        let eps = r.f32()?;
+        let t = r.u64()? as usize;
+        let layers = r.u64()? as usize;
+        let mut state = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for _ in 0..layers {
+            for buf in state.iter_mut() {
+                let len = r.u64()? as usize;
+                buf.push(
+                    (0..len)
+                        .map(|_| r.f32())
+                        .collect::<NeuroxResult<Vec<f32>>>()?,
+                );
+            }
+        }
+        if !r.bytes.is_empty() {
+            return Err(NeuroxError::InvalidArgument(
+                "trailing bytes in Adam state file".into(),
+            ));
+        }
+        let [m_w, v_w, m_b, v_b] = state;
+        Ok(Adam {
+            lr,
+            beta1,
+            beta2,
+            eps,
+            t,
+            m_w,
+            v_w,
+            m_b,
+            v_b,
+        })
+    }
+
+    /// Allocates zeroed moment buffers for any layer whose parameter sizes do not match.
+    fn ensure_state(&mut self, layers: &[Dense]) {
+        for state in [&mut self.m_w, &mut self.v_w, &mut self.m_b, &mut self.v_b] {
+            state.resize_with(layers.len(), Vec::new);
+        }
+        for (li, l) in layers.iter().enumerate() {
+            if self.m_w[li].len() != l.w.data.len() {
+                self.m_w[li] = vec![0.0; l.w.data.len()];
+                self.v_w[li] = vec![0.0; l.w.data.len()];
+            }
+            if self.m_b[li].len() != l.b.data.len() {
+                self.m_b[li] = vec![0.0; l.b.data.len()];
+                self.v_b[li] = vec![0.0; l.b.data.len()];
+            }
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, layers: &mut [Dense]) {
+        Adam::step(self, layers)
+    }
+
+    fn lr(&self) -> f32 {
+        self.lr
+    }
+
+    fn set_lr(&mut self, lr: f32) {
+        self.lr = lr;
+    }
+}
+
+/// The AdaGrad optimization algorithm.
+///
+/// Accumulates the sum of squared gradients of every parameter and updates it with
+/// `param -= lr * grad / (sqrt(accumulated) + eps)`. Unlike RMSprop the accumulator
+/// never decays, so the effective learning rate only shrinks: parameters with large or
+/// frequent gradients slow down, while rarely updated ones (e.g. sparse features) keep
+/// taking large steps.
+pub struct AdaGrad {
+    pub lr: f32,
+    pub eps: f32,
+    // Per-layer sums of squared gradients for weights and biases
+    pub g2_w: Vec<Vec<f32>>,
+    pub g2_b: Vec<Vec<f32>>,
+}
+
+impl AdaGrad {
+    /// Creates a new AdaGrad optimizer with zeroed accumulators sized for `layers`.
+    pub fn new(lr: f32, layers: &[Dense]) -> Self {
+        Self {
+            lr,
+            eps: constants::ADAGRAD_EPS,
+            g2_w: layers.iter().map(|l| vec![0.0; l.w.data.len()]).collect(),
+            g2_b: layers.iter().map(|l| vec![0.0; l.b.data.len()]).collect(),
+        }
+    }
+
+    /// Performs a single AdaGrad optimization step.
+    ///
+    /// As with `Adam::step`, accumulators are resized to match `layers` first, so new or
+    /// reshaped layers start from zero.
+    pub fn step(&mut self, layers: &mut [Dense]) {
+        for state in [&mut self.g2_w, &mut self.g2_b] {
+            state.resize_with(layers.len(), Vec::new);
+        }
+        let (lr, eps) = (self.lr, self.eps);
+        let update = |params: &mut [f32], grads: &[f32], acc: &mut Vec<f32>| {
+            if acc.len() != params.len() {
+                *acc = vec![0.0; params.len()];
+            }
+            for ((p, &g), a) in params.iter_mut().zip(grads).zip(acc.iter_mut()) {
+                *a += g * g;
+                *p -= lr * g / (a.sqrt() + eps);
+            }
+        };
+        for (li, l) in layers.iter_mut().enumerate() {
+            if let Some(gw) = &l.grad_w {
+                update(&mut l.w.data, &gw.data, &mut self.g2_w[li]);
+            }
+            if let Some(gb) = &l.grad_b {
+                update(&mut l.b.data, &gb.data, &mut self.g2_b[li]);
+            }
+            l.apply_mask();
+        }
+    }
+}
+
+impl Optimizer for AdaGrad {
+    fn step(&mut self, layers: &mut [Dense]) {
+        AdaGrad::step(self, layers)
+    }
+
+    fn lr(&self) -> f32 {
+        self.lr
+    }
+
+    fn set_lr(&mut self, lr: f32) {
+        self.lr = lr;
+    }
+}
+
+/// Cursor over the little-endian fields of a saved optimizer state.
+struct StateReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl StateReader<'_> {
+    fn take<const N: usize>(&mut self) -> NeuroxResult<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(NeuroxError::InvalidArgument(
+                "truncated Adam state file".into(),
+            ));
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn f32(&mut self) -> NeuroxResult<f32> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> NeuroxResult<u64> {
+        Ok(u64::from_le_bytes(self.take()?))
     }
 }
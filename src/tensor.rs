@@ -1,11 +1,18 @@
 //! Defines the core `Tensor` struct and its associated methods.
 
 use crate::errors::{NeuroxError, NeuroxResult};
+use crate::{ops, utils};
 use rand::Rng;
 use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
 /// A 2D tensor representing a matrix of `f32` values, stored in row-major order.
-#[derive(Clone)]
+///
+/// Equality (`==`) is exact: the shapes must match and every element must compare equal
+/// as an `f32`, so a `NaN` entry makes tensors unequal. Use `approx_eq` to allow for
+/// rounding differences.
+#[derive(Clone, PartialEq)]
 pub struct Tensor {
     pub data: Vec<f32>,
     pub rows: usize,
@@ -36,12 +43,58 @@ impl Tensor {
         Self { data, rows, cols }
     }
 
+    /// Creates a new tensor from nested rows, flattening them in row-major order.
+    ///
+    /// An empty `rows` yields a `(0, 0)` tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if the rows do not all have the same length.
+    pub fn from_rows(rows: Vec<Vec<f32>>) -> NeuroxResult<Tensor> {
+        let r = rows.len();
+        let c = rows.first().map_or(0, |row| row.len());
+        let mut data = Vec::with_capacity(r * c);
+        for row in rows {
+            if row.len() != c {
+                return Err(NeuroxError::InvalidArgument(
+                    "rows must all have the same length".into(),
+                ));
+            }
+            data.extend_from_slice(&row);
+        }
+        Ok(Tensor::from_data(data, r, c))
+    }
+
     /// Creates a new tensor with random values sampled from a uniform distribution between -1.0 and 1.0.
+    ///
+    /// Draws from the crate's RNG, so results are reproducible after `utils::set_seed`.
     pub fn random(rows: usize, cols: usize) -> Self {
-        let mut rng = rand::rng();
-        let data = (0..rows * cols)
-            .map(|_| rng.random_range(-1.0..1.0))
-            .collect();
+        Self::random_uniform(rows, cols, -1.0, 1.0)
+    }
+
+    /// Creates a new tensor with values sampled uniformly from `[low, high)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low >= high`.
+    pub fn random_uniform(rows: usize, cols: usize, low: f32, high: f32) -> Self {
+        let data = utils::with_rng(|rng| {
+            (0..rows * cols)
+                .map(|_| rng.random_range(low..high))
+                .collect()
+        });
+        Self { data, rows, cols }
+    }
+
+    /// Creates a new tensor with values sampled from a normal distribution `N(mean, std²)`.
+    ///
+    /// Uses the Box-Muller transform over the crate's RNG.
+    pub fn random_normal(rows: usize, cols: usize, mean: f32, std: f32) -> Self {
+        let data = utils::with_rng(|rng| {
+            (0..rows * cols)
+                .map(|_| mean + std * utils::sample_normal(rng))
+                .collect()
+        });
         Self { data, rows, cols }
     }
 
@@ -68,6 +121,19 @@ impl Tensor {
         self.data[r * self.cols + c] = v;
     }
 
+    /// Returns a copy with `eps` added to the element at flat (row-major) `index`.
+    ///
+    /// Intended for finite-difference gradient checks; `self` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn perturbed(&self, index: usize, eps: f32) -> Tensor {
+        let mut out = self.clone();
+        out.data[index] += eps;
+        out
+    }
+
     /// Applies a function element-wise to the tensor, returning a new `Tensor`.
     pub fn map<F>(&self, mut f: F) -> Tensor
     where
@@ -77,6 +143,100 @@ impl Tensor {
         Tensor::from_data(d, self.rows, self.cols)
     }
 
+    /// Returns whether `other` has the same shape and every element is within `tol`.
+    pub fn approx_eq(&self, other: &Tensor, tol: f32) -> bool {
+        self.shape() == other.shape()
+            && self
+                .data
+                .iter()
+                .zip(&other.data)
+                .all(|(a, b)| (a - b).abs() <= tol)
+    }
+
+    /// Rounds every element to `decimals` digits after the decimal point.
+    ///
+    /// Halfway cases round away from zero. Handy for comparing results at a fixed,
+    /// platform-independent precision.
+    pub fn round_to(&self, decimals: u32) -> Tensor {
+        let scale = 10f64.powi(decimals as i32);
+        self.map(|v| ((v as f64 * scale).round() / scale) as f32)
+    }
+
+    /// Returns `(rows, cols, data)` with the elements widened to `f64`, in row-major order.
+    ///
+    /// There is no `f64` tensor type; this is for handing data to code that needs double
+    /// precision. `from_f64` converts back, keeping the shape.
+    pub fn to_f64(&self) -> (usize, usize, Vec<f64>) {
+        let data = self.data.iter().map(|&v| v as f64).collect();
+        (self.rows, self.cols, data)
+    }
+
+    /// Creates a tensor from `(rows, cols, data)` as returned by `to_f64`, rounding each
+    /// value to the nearest `f32`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len()` is not equal to `rows * cols`.
+    pub fn from_f64((rows, cols, data): (usize, usize, Vec<f64>)) -> Tensor {
+        Tensor::from_data(data.into_iter().map(|v| v as f32).collect(), rows, cols)
+    }
+
+    /// Returns the element-wise absolute value.
+    pub fn abs(&self) -> Tensor {
+        self.map(f32::abs)
+    }
+
+    /// Returns the element-wise sign: `-1.0`, `0.0` or `1.0`.
+    ///
+    /// Unlike `f32::signum`, both zeros map to `0.0`; NaN stays NaN.
+    pub fn signum(&self) -> Tensor {
+        self.map(|v| if v == 0.0 { 0.0 } else { v.signum() })
+    }
+
+    /// Applies a function to each whole row, returning a new `Tensor`.
+    ///
+    /// Unlike `map`, the closure sees the full row, which suits per-row transforms such as
+    /// normalization.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if the closure returns a row whose length is
+    /// not `cols`.
+    pub fn map_rows<F>(&self, mut f: F) -> NeuroxResult<Tensor>
+    where
+        F: FnMut(&[f32]) -> Vec<f32>,
+    {
+        let mut data = Vec::with_capacity(self.data.len());
+        for i in 0..self.rows {
+            let row = f(&self.data[i * self.cols..(i + 1) * self.cols]);
+            if row.len() != self.cols {
+                return Err(NeuroxError::ShapeMismatch(format!(
+                    "map_rows closure returned {} values for a row of {}",
+                    row.len(),
+                    self.cols
+                )));
+            }
+            data.extend(row);
+        }
+        Ok(Tensor::from_data(data, self.rows, self.cols))
+    }
+
+    /// Scales every row to unit L2 norm. All-zero rows are left as zeros.
+    pub fn normalize_rows(&self) -> Tensor {
+        let mut out = self.clone();
+        if self.cols > 0 {
+            for row in out.data.chunks_mut(self.cols) {
+                normalize(row);
+            }
+        }
+        out
+    }
+
+    /// Scales every column to unit L2 norm. All-zero columns are left as zeros.
+    pub fn normalize_cols(&self) -> Tensor {
+        self.transpose().normalize_rows().transpose()
+    }
+
     /// Adds a bias row vector to each row of this tensor (broadcasts).
     ///
     /// # Errors
@@ -88,14 +248,422 @@ impl Tensor {
                 "bias shape must be (1, cols)".into(),
             ));
         }
-        let mut out = self.clone();
+        ops::add(self, bias)
+    }
+
+    /// Subtracts a `(1, cols)` row vector from each row of this tensor (broadcasts).
+    ///
+    /// This is the inverse of `add_row_broadcast`, e.g. for mean-centering features.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `v`'s shape is not `(1, self.cols)`.
+    pub fn sub_row_broadcast(&self, v: &Tensor) -> NeuroxResult<Tensor> {
+        if v.rows != 1 || v.cols != self.cols {
+            return Err(NeuroxError::ShapeMismatch(
+                "row vector shape must be (1, cols)".into(),
+            ));
+        }
+        ops::sub(self, v)
+    }
+
+    /// Scales each row of this tensor element-wise by a `(1, cols)` vector (broadcasts).
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `v`'s shape is not `(1, self.cols)`.
+    pub fn mul_row_broadcast(&self, v: &Tensor) -> NeuroxResult<Tensor> {
+        if v.rows != 1 || v.cols != self.cols {
+            return Err(NeuroxError::ShapeMismatch(
+                "row scale shape must be (1, cols)".into(),
+            ));
+        }
+        ops::mul(self, v)
+    }
+
+    /// Scales each column of this tensor element-wise by a `(rows, 1)` vector (broadcasts).
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if `v`'s shape is not `(self.rows, 1)`.
+    pub fn mul_col_broadcast(&self, v: &Tensor) -> NeuroxResult<Tensor> {
+        if v.rows != self.rows || v.cols != 1 {
+            return Err(NeuroxError::ShapeMismatch(
+                "column scale shape must be (rows, 1)".into(),
+            ));
+        }
+        ops::mul(self, v)
+    }
+
+    /// Returns rows `start` (inclusive) to `end` (exclusive) as a new tensor.
+    ///
+    /// An empty range yields a `(0, cols)` tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` unless `start <= end <= rows`.
+    pub fn slice_rows(&self, start: usize, end: usize) -> NeuroxResult<Tensor> {
+        if start > end || end > self.rows {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "row range {}..{} out of bounds for {} rows",
+                start, end, self.rows
+            )));
+        }
+        let data = self.data[start * self.cols..end * self.cols].to_vec();
+        Ok(Tensor::from_data(data, end - start, self.cols))
+    }
+
+    /// Splits the tensor into rows `[0, row)` and `[row, rows)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `row > rows`.
+    pub fn split_at_row(&self, row: usize) -> NeuroxResult<(Tensor, Tensor)> {
+        Ok((self.slice_rows(0, row)?, self.slice_rows(row, self.rows)?))
+    }
+
+    /// Returns the column index of the largest value in each row.
+    ///
+    /// Ties resolve to the lowest index. An empty row yields `0`.
+    pub fn argmax_rows(&self) -> Vec<usize> {
+        (0..self.rows)
+            .map(|i| {
+                let mut best = 0;
+                for j in 1..self.cols {
+                    if self.get(i, j) > self.get(i, best) {
+                        best = j;
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Returns the `k` largest values of row `i` and their column indices, largest first.
+    ///
+    /// Ties resolve to the lower index, matching `argmax_rows`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `i >= rows` or `k > cols`.
+    pub fn topk_row(&self, i: usize, k: usize) -> NeuroxResult<(Vec<usize>, Vec<f32>)> {
+        if i >= self.rows || k > self.cols {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "topk_row({}, {}) out of bounds for a {}x{} tensor",
+                i, k, self.rows, self.cols
+            )));
+        }
+        let row = &self.data[i * self.cols..(i + 1) * self.cols];
+        let mut order: Vec<usize> = (0..self.cols).collect();
+        order.sort_by(|&a, &b| row[b].total_cmp(&row[a]));
+        order.truncate(k);
+        let values = order.iter().map(|&j| row[j]).collect();
+        Ok((order, values))
+    }
+
+    /// Returns the main diagonal of a square matrix as an `(n, 1)` column.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if the tensor is not square.
+    pub fn diag(&self) -> NeuroxResult<Tensor> {
+        if self.rows != self.cols {
+            return Err(NeuroxError::ShapeMismatch(
+                "diag requires a square matrix".into(),
+            ));
+        }
+        let data = (0..self.rows).map(|i| self.get(i, i)).collect();
+        Ok(Tensor::from_data(data, self.rows, 1))
+    }
+
+    /// Returns the sum of the main diagonal of a square matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::ShapeMismatch` if the tensor is not square.
+    pub fn trace(&self) -> NeuroxResult<f32> {
+        Ok(self.diag()?.data.iter().sum())
+    }
+
+    /// Checks that every row is a probability distribution.
+    ///
+    /// Each entry must lie in `[0, 1]` and each row must sum to within `tol` of `1.0`.
+    /// Intended as a development-time check after Softmax.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` naming the first offending row.
+    pub fn assert_probability_rows(&self, tol: f32) -> NeuroxResult<()> {
+        for i in 0..self.rows {
+            let row = &self.data[i * self.cols..(i + 1) * self.cols];
+            if let Some(&bad) = row.iter().find(|v| !(0.0..=1.0).contains(*v)) {
+                return Err(NeuroxError::InvalidArgument(format!(
+                    "row {} has entry {} outside [0, 1]",
+                    i, bad
+                )));
+            }
+            let sum: f32 = row.iter().sum();
+            if (sum - 1.0).abs() > tol {
+                return Err(NeuroxError::InvalidArgument(format!(
+                    "row {} sums to {}, expected 1 within {}",
+                    i, sum, tol
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the Shannon entropy `-sum(p * ln(p))` of each row as a `(rows, 1)` tensor.
+    ///
+    /// Rows are assumed to be probability distributions (see `assert_probability_rows`);
+    /// zero entries contribute nothing, following `0 * ln(0) = 0`. A one-hot row scores
+    /// `0` and a uniform row over `n` classes the maximum `ln(n)`.
+    pub fn row_entropy(&self) -> Tensor {
+        let data = (0..self.rows)
+            .map(|i| {
+                -self.data[i * self.cols..(i + 1) * self.cols]
+                    .iter()
+                    .filter(|&&p| p > 0.0)
+                    .map(|&p| p * p.ln())
+                    .sum::<f32>()
+            })
+            .collect();
+        Tensor::from_data(data, self.rows, 1)
+    }
+
+    /// Computes the variance along an axis with `ddof` delta degrees of freedom.
+    ///
+    /// The divisor is `n - ddof`, so `ddof = 0` gives the population variance and
+    /// `ddof = 1` the unbiased sample variance. Uses two passes in `f64` (mean, then
+    /// squared deviations) rather than `E[x²] - E[x]²`, which cancels badly for data with
+    /// a large mean. `axis = 1` reduces each row to a `(rows, 1)` result and `axis = 0`
+    /// reduces each column to a `(1, cols)` result.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `axis` is not `0` or `1`, or if the
+    /// reduced length is not greater than `ddof`.
+    pub fn var_axis(&self, axis: usize, ddof: usize) -> NeuroxResult<Tensor> {
+        let (lanes, len) = match axis {
+            0 => (self.cols, self.rows),
+            1 => (self.rows, self.cols),
+            _ => {
+                return Err(NeuroxError::InvalidArgument(format!(
+                    "var_axis axis must be 0 or 1, got {}",
+                    axis
+                )));
+            }
+        };
+        if len <= ddof {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "variance over {} values needs ddof < {}, got {}",
+                len, len, ddof
+            )));
+        }
+        let at = |lane: usize, k: usize| -> f64 {
+            if axis == 0 {
+                self.get(k, lane) as f64
+            } else {
+                self.get(lane, k) as f64
+            }
+        };
+        let data = (0..lanes)
+            .map(|lane| {
+                let mean = (0..len).map(|k| at(lane, k)).sum::<f64>() / len as f64;
+                let ss: f64 = (0..len).map(|k| (at(lane, k) - mean).powi(2)).sum();
+                (ss / (len - ddof) as f64) as f32
+            })
+            .collect();
+        Ok(if axis == 0 {
+            Tensor::from_data(data, 1, lanes)
+        } else {
+            Tensor::from_data(data, lanes, 1)
+        })
+    }
+
+    /// Computes the standard deviation along an axis: the square root of `var_axis`.
+    ///
+    /// # Errors
+    ///
+    /// Same as `var_axis`.
+    pub fn std_axis(&self, axis: usize, ddof: usize) -> NeuroxResult<Tensor> {
+        Ok(self.var_axis(axis, ddof)?.map(f32::sqrt))
+    }
+
+    /// Estimates the largest singular value by power iteration on `WᵀW`.
+    ///
+    /// Starts from the all-ones vector and runs `iters` iterations; more iterations give a
+    /// tighter estimate when the top two singular values are close. Returns `0.0` for an
+    /// empty or all-zero matrix.
+    pub fn spectral_norm(&self, iters: usize) -> f32 {
+        let t = self.transpose();
+        let mut v = vec![1.0; self.cols];
+        normalize(&mut v);
+        for _ in 0..iters {
+            let u = self.mat_vec(&v);
+            v = t.mat_vec(&u);
+            if normalize(&mut v) == 0.0 {
+                return 0.0;
+            }
+        }
+        self.mat_vec(&v).iter().map(|x| x * x).sum::<f32>().sqrt()
+    }
+
+    /// Estimates the condition number `sigma_max / sigma_min` from the extreme singular
+    /// values.
+    ///
+    /// Works on the Gram matrix of the smaller side in `f64`: power iteration finds its
+    /// largest eigenvalue `sigma_max²`, then power iteration on the shifted matrix
+    /// `sigma_max² I - G` finds `sigma_max² - sigma_min²`. Each phase runs `iters`
+    /// iterations from the all-ones vector; the second converges slowly when the two
+    /// smallest singular values are close. Returns `f32::INFINITY` for a singular (or
+    /// all-zero) matrix.
+    pub fn condition_number(&self, iters: usize) -> f32 {
+        let m = if self.rows < self.cols {
+            self.transpose()
+        } else {
+            self.clone()
+        };
+        let k = m.cols;
+        let mut gram = vec![0.0f64; k * k];
+        for i in 0..m.rows {
+            let row = &m.data[i * k..(i + 1) * k];
+            for a in 0..k {
+                for b in 0..k {
+                    gram[a * k + b] += row[a] as f64 * row[b] as f64;
+                }
+            }
+        }
+        // Largest eigenvalue of `shift * I + scale * G` by power iteration.
+        let top_eigenvalue = |shift: f64, scale: f64| {
+            let apply = |v: &[f64]| -> Vec<f64> {
+                (0..k)
+                    .map(|a| {
+                        let gv: f64 = (0..k).map(|b| gram[a * k + b] * v[b]).sum();
+                        shift * v[a] + scale * gv
+                    })
+                    .collect()
+            };
+            let mut v = vec![1.0 / (k as f64).sqrt(); k];
+            for _ in 0..iters {
+                let w = apply(&v);
+                let norm = w.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if norm == 0.0 {
+                    return 0.0;
+                }
+                v = w.iter().map(|x| x / norm).collect();
+            }
+            apply(&v).iter().zip(&v).map(|(a, b)| a * b).sum::<f64>()
+        };
+        let max = top_eigenvalue(0.0, 1.0);
+        let min = max - top_eigenvalue(max, -1.0);
+        if max <= 0.0 || min <= 0.0 {
+            return f32::INFINITY;
+        }
+        (max / min).sqrt() as f32
+    }
+
+    /// Multiplies this matrix by the column vector `v` (length `cols`).
+    pub(crate) fn mat_vec(&self, v: &[f32]) -> Vec<f32> {
+        (0..self.rows)
+            .map(|i| {
+                self.data[i * self.cols..(i + 1) * self.cols]
+                    .iter()
+                    .zip(v)
+                    .map(|(a, b)| a * b)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Counts the values in `bins` equal-width bins, returning `(edges, counts)`.
+    ///
+    /// `edges` has `bins + 1` entries. Each bin is half-open except the last, which also
+    /// includes the upper edge. With `range = None` the min and max of the finite values
+    /// are used; values outside the range and NaNs are not counted. A degenerate range
+    /// (e.g. constant data) is widened by `0.5` on each side, and a tensor with no finite
+    /// values uses `(0, 1)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::InvalidArgument` if `bins` is zero, or if an explicit `range`
+    /// is not finite or has `min > max`.
+    pub fn histogram(
+        &self,
+        bins: usize,
+        range: Option<(f32, f32)>,
+    ) -> NeuroxResult<(Vec<f32>, Vec<usize>)> {
+        if bins == 0 {
+            return Err(NeuroxError::InvalidArgument("bins must be positive".into()));
+        }
+        if let Some((lo, hi)) = range
+            && !(lo.is_finite() && hi.is_finite() && lo <= hi)
+        {
+            return Err(NeuroxError::InvalidArgument(format!(
+                "histogram range ({}, {}) must be finite with min <= max",
+                lo, hi
+            )));
+        }
+        let (mut lo, mut hi) = range.unwrap_or_else(|| {
+            let finite = self.data.iter().copied().filter(|v| v.is_finite());
+            let lo = finite.clone().fold(f32::INFINITY, f32::min);
+            let hi = finite.fold(f32::NEG_INFINITY, f32::max);
+            if lo > hi { (0.0, 1.0) } else { (lo, hi) }
+        });
+        if hi <= lo {
+            lo -= 0.5;
+            hi += 0.5;
+        }
+        let width = (hi - lo) / bins as f32;
+        let edges = (0..=bins).map(|i| lo + width * i as f32).collect();
+        let mut counts = vec![0; bins];
+        for &v in &self.data {
+            if (lo..=hi).contains(&v) {
+                let bin = (((v - lo) / width) as usize).min(bins - 1);
+                counts[bin] += 1;
+            }
+        }
+        Ok((edges, counts))
+    }
+
+    /// Writes the tensor to `path` as comma-separated rows with no header.
+    ///
+    /// Values use the shortest representation that parses back to the same `f32`, so
+    /// `data::tensor_from_csv` reproduces the tensor exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::Io` if the file cannot be written.
+    pub fn to_csv(&self, path: &str) -> NeuroxResult<()> {
+        self.write_csv(path, None)
+    }
+
+    /// Writes the tensor to `path` like `to_csv`, with `precision` digits after the
+    /// decimal point.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NeuroxError::Io` if the file cannot be written.
+    pub fn to_csv_with_precision(&self, path: &str, precision: usize) -> NeuroxResult<()> {
+        self.write_csv(path, Some(precision))
+    }
+
+    fn write_csv(&self, path: &str, precision: Option<usize>) -> NeuroxResult<()> {
+        let mut out = BufWriter::new(File::create(path)?);
         for i in 0..self.rows {
             for j in 0..self.cols {
-                let idx = i * self.cols + j;
-                out.data[idx] += bias.data[j];
+                if j > 0 {
+                    write!(out, ",")?;
+                }
+                match precision {
+                    Some(p) => write!(out, "{:.*}", p, self.get(i, j))?,
+                    None => write!(out, "{}", self.get(i, j))?,
+                }
             }
+            writeln!(out)?;
         }
-        Ok(out)
+        out.flush()?;
+        Ok(())
     }
 
     /// Creates a new tensor of `rows` x `cols` initialized with zeros. Alias for `new`.
@@ -103,6 +671,16 @@ impl Tensor {
         Self::new(rows, cols)
     }
 
+    /// Creates a zero tensor with the same shape as `self`.
+    pub fn zeros_like(&self) -> Tensor {
+        Self::zeros(self.rows, self.cols)
+    }
+
+    /// Creates a tensor of ones with the same shape as `self`.
+    pub fn ones_like(&self) -> Tensor {
+        Self::from_data(vec![1.0; self.data.len()], self.rows, self.cols)
+    }
+
     /// Returns a new `Tensor` that is the transpose of this one.
     pub fn transpose(&self) -> Tensor {
         let mut out = vec![0.0; self.rows * self.cols];
@@ -115,6 +693,16 @@ impl Tensor {
     }
 }
 
+/// Scales `v` to unit length in place and returns its original norm. A zero vector is
+/// left unchanged.
+pub(crate) fn normalize(v: &mut [f32]) -> f32 {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    norm
+}
+
 /// Provides a truncated, pretty-printed format for debugging tensors.
 impl fmt::Debug for Tensor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -1,7 +1,178 @@
-use rand::{SeedableRng, rngs::StdRng};
+//! Miscellaneous helpers, including the crate-wide random number generator.
 
-/// Set global seed for reproducibility (affects rand::thread_rng only if used indirectly).
+use crate::errors::{NeuroxError, NeuroxResult};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Size in bytes of the serialized RNG state: seed (32) + stream (8) + word position (16).
+const RNG_STATE_LEN: usize = 32 + 8 + 16;
+
+/// Whether parallel kernels must use the serial summation order. See `set_deterministic`.
+static DETERMINISTIC: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    // Seeded from OS entropy until `set_seed` or `set_rng_state` is called.
+    static RNG: RefCell<ChaCha12Rng> = RefCell::new(ChaCha12Rng::from_rng(&mut rand::rng()));
+}
+
+/// Runs `f` with exclusive access to the crate's thread-local RNG.
+pub(crate) fn with_rng<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut ChaCha12Rng) -> R,
+{
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Runs `f` with the crate's RNG temporarily reseeded from `seed`, then restores the
+/// previous generator, so draws made outside `f` are unaffected.
+pub(crate) fn with_seed<F, R>(seed: u64, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let saved = with_rng(|rng| std::mem::replace(rng, ChaCha12Rng::seed_from_u64(seed)));
+    let out = f();
+    with_rng(|rng| *rng = saved);
+    out
+}
+
+/// Derives an independent seed for stream `index` from `seed` with the SplitMix64 mixer.
+pub(crate) fn derive_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Draws a standard normal sample with the Box-Muller transform.
+pub(crate) fn sample_normal<R: Rng>(rng: &mut R) -> f32 {
+    // 1 - u keeps the argument of ln in (0, 1].
+    let u1: f32 = 1.0 - rng.random::<f32>();
+    let u2: f32 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+/// Set global seed for reproducibility.
+///
+/// Reseeds the thread-local RNG used by `Tensor::random` and the other random
+/// initializers in this crate.
 pub fn set_seed(seed: u64) {
-    let _rng = StdRng::seed_from_u64(seed);
+    with_rng(|rng| *rng = ChaCha12Rng::seed_from_u64(seed));
     println!("Seed set to {}", seed);
 }
+
+/// Chooses between the serial summation order and a faster one in parallel kernels.
+///
+/// Only affects builds with the `parallel` feature; serial builds always use the
+/// sequential order. Parallel `ops::matmul` splits work by output row in both modes.
+/// When `true` (the default), every dot product is accumulated sequentially, so results
+/// are bit-identical to a serial build. When `false`, each dot product is accumulated in
+/// several interleaved partial sums, which is faster for long inner dimensions but rounds
+/// differently, so the last bits of results may differ from the default mode. Either
+/// way, results do not depend on the thread count or scheduling.
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, Ordering::Relaxed);
+}
+
+/// Returns whether parallel kernels use a fixed reduction order.
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+/// Exports the current state of the crate's RNG as bytes.
+///
+/// Restoring these bytes with `set_rng_state` makes subsequent random draws
+/// identical to those that would have followed the export.
+pub fn rng_state() -> Vec<u8> {
+    with_rng(|rng| {
+        let mut out = Vec::with_capacity(RNG_STATE_LEN);
+        out.extend_from_slice(&rng.get_seed());
+        out.extend_from_slice(&rng.get_stream().to_le_bytes());
+        out.extend_from_slice(&rng.get_word_pos().to_le_bytes());
+        out
+    })
+}
+
+/// Restores the crate's RNG from bytes produced by `rng_state`.
+///
+/// # Errors
+///
+/// Returns `NeuroxError::InvalidArgument` if `bytes` is not a serialized RNG state.
+pub fn set_rng_state(bytes: &[u8]) -> NeuroxResult<()> {
+    if bytes.len() != RNG_STATE_LEN {
+        return Err(NeuroxError::InvalidArgument(format!(
+            "rng state must be {} bytes, got {}",
+            RNG_STATE_LEN,
+            bytes.len()
+        )));
+    }
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&bytes[..32]);
+    let stream = u64::from_le_bytes(bytes[32..40].try_into().unwrap());
+    let word_pos = u128::from_le_bytes(bytes[40..].try_into().unwrap());
+
+    let mut restored = ChaCha12Rng::from_seed(seed);
+    restored.set_stream(stream);
+    restored.set_word_pos(word_pos);
+    with_rng(|rng| *rng = restored);
+    Ok(())
+}
+
+/// Returns the least-squares slope of the last `window` values of a loss history.
+///
+/// The values are regressed against their step index, so the slope is the loss change
+/// per step: clearly negative while training improves, near zero or positive on a
+/// plateau. Uses the whole history if it is shorter than `window`, and returns `0.0`
+/// when fewer than two values are available.
+pub fn loss_slope(history: &[f32], window: usize) -> f32 {
+    let recent = &history[history.len().saturating_sub(window)..];
+    let n = recent.len();
+    if n < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1) as f64 / 2.0;
+    let mean_y = recent.iter().map(|&y| y as f64).sum::<f64>() / n as f64;
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for (i, &y) in recent.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        sxy += dx * (y as f64 - mean_y);
+        sxx += dx * dx;
+    }
+    (sxy / sxx) as f32
+}
+
+/// Exponential moving average of a stream of values, e.g. to log a smoothed training loss.
+///
+/// Each `update` sets the average to `decay * average + (1 - decay) * value`. The first
+/// value initializes the average directly, so early readings are not biased toward zero.
+#[derive(Clone, Debug)]
+pub struct EmaMeter {
+    decay: f32,
+    value: Option<f32>,
+}
+
+impl EmaMeter {
+    /// Creates an empty meter. Larger `decay` gives smoother but slower-moving averages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `decay` is not in `[0, 1)`.
+    pub fn new(decay: f32) -> Self {
+        assert!((0.0..1.0).contains(&decay), "decay must be in [0, 1)");
+        Self { decay, value: None }
+    }
+
+    /// Folds `value` into the moving average.
+    pub fn update(&mut self, value: f32) {
+        self.value = Some(match self.value {
+            Some(avg) => self.decay * avg + (1.0 - self.decay) * value,
+            None => value,
+        });
+    }
+
+    /// Returns the current average, or `0.0` before the first update.
+    pub fn value(&self) -> f32 {
+        self.value.unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,139 @@
+use neurox::Tensor;
+use neurox::activations;
+
+#[test]
+fn softmax_axis_normalizes_columns() {
+    let x = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0, 0.5, -1.0], 3, 2);
+
+    let cols = activations::softmax_axis(&x, 0).expect("softmax failed");
+    for j in 0..2 {
+        let sum: f32 = (0..3).map(|i| cols.get(i, j)).sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+    }
+
+    let rows = activations::softmax_axis(&x, 1).expect("softmax failed");
+    assert_eq!(rows.data, activations::softmax(&x).data);
+
+    assert!(activations::softmax_axis(&x, 2).is_err());
+}
+
+#[test]
+fn leaky_relu_applies_negative_slope() {
+    let x = Tensor::from_data(vec![-2.0, -0.5, 0.0, 1.5], 2, 2);
+    assert_eq!(
+        activations::leaky_relu(&x, 0.1).data,
+        vec![-0.2, -0.05, 0.0, 1.5]
+    );
+    assert_eq!(
+        activations::leaky_relu_grad(&x, 0.1).data,
+        vec![0.1, 0.1, 0.1, 1.0]
+    );
+
+    use neurox::layers::{Activation, Dense};
+    let w = Tensor::from_data(vec![1.0], 1, 1);
+    let mut layer =
+        Dense::from_weights(w, Tensor::zeros(1, 1), Activation::LeakyReLU(0.2)).unwrap();
+    let input = Tensor::from_data(vec![-3.0, 2.0], 2, 1);
+    let out = layer.forward(&input).unwrap();
+    assert!((out.data[0] + 0.6).abs() < 1e-6 && out.data[1] == 2.0);
+    let grad = layer
+        .backward(&Tensor::from_data(vec![1.0, 1.0], 2, 1))
+        .unwrap();
+    assert!((grad.data[0] - 0.2).abs() < 1e-6 && grad.data[1] == 1.0);
+    assert!((layer.grad_w.unwrap().data[0] - 1.4).abs() < 1e-6);
+}
+
+#[test]
+fn elu_is_continuous_and_saturates() {
+    let alpha = 1.5;
+    let x = Tensor::from_data(vec![-1e-4, 0.0, 1e-4, -1000.0, 2.0, -1.0], 3, 2);
+    let y = activations::elu(&x, alpha);
+    assert!(y.data.iter().all(|v| v.is_finite()));
+    assert!((y.data[0] - y.data[2]).abs() < 1e-3);
+    assert_eq!(y.data[1], 0.0);
+    assert_eq!(y.data[3], -alpha);
+    assert_eq!(y.data[4], 2.0);
+
+    let g = activations::elu_grad(&x, alpha);
+    assert_eq!(g.data[4], 1.0);
+    assert_eq!(g.data[3], 0.0);
+    // On the negative branch the derivative equals elu(x) + alpha.
+    assert!((g.data[5] - (y.data[5] + alpha)).abs() < 1e-6);
+    let h = 1e-3;
+    let fd = (activations::elu(&Tensor::from_data(vec![-1.0 + h], 1, 1), alpha).data[0]
+        - activations::elu(&Tensor::from_data(vec![-1.0 - h], 1, 1), alpha).data[0])
+        / (2.0 * h);
+    assert!((g.data[5] - fd).abs() < 1e-3);
+}
+
+#[test]
+fn gelu_grad_matches_finite_differences() {
+    let points = [-3.0, -1.2, -0.3, 0.0, 0.4, 1.0, 2.5];
+    let x = Tensor::from_data(points.to_vec(), 1, points.len());
+    let grad = activations::gelu_grad(&x);
+    let h = 1e-2;
+    let up = activations::gelu(&x.map(|v| v + h));
+    let down = activations::gelu(&x.map(|v| v - h));
+    for (i, x) in points.iter().enumerate() {
+        let fd = (up.data[i] - down.data[i]) / (2.0 * h);
+        assert!(
+            (grad.data[i] - fd).abs() < 1e-3,
+            "x = {}: {} vs {}",
+            x,
+            grad.data[i],
+            fd
+        );
+    }
+    let y = activations::gelu(&x);
+    assert_eq!(y.data[3], 0.0);
+    assert!((y.data[5] - 0.841192).abs() < 1e-5);
+    assert!(y.data[0].abs() < 0.01);
+}
+
+#[test]
+fn inplace_activations_match_allocating_versions() {
+    let x = Tensor::from_data(vec![-2.0, -0.5, 0.0, 0.3, 1.5, 4.0], 2, 3);
+
+    let mut y = x.clone();
+    activations::relu_inplace(&mut y);
+    assert_eq!(y, activations::relu(&x));
+    assert_eq!(activations::relu_grad(&y), activations::relu_grad(&x));
+
+    let mut y = x.clone();
+    activations::leaky_relu_inplace(&mut y, 0.1);
+    assert_eq!(y, activations::leaky_relu(&x, 0.1));
+
+    let mut y = x.clone();
+    activations::sigmoid_inplace(&mut y);
+    assert_eq!(y, activations::sigmoid(&x));
+
+    let mut y = x.clone();
+    activations::tanh_inplace(&mut y);
+    assert_eq!(y, activations::tanh(&x));
+}
+
+#[test]
+fn softplus_is_stable_and_its_grad_is_sigmoid() {
+    let x = Tensor::from_data(vec![-100.0, -2.0, 0.0, 1.5, 50.0, 100.0], 1, 6);
+    let y = activations::softplus(&x);
+    assert!(y.data.iter().all(|v| v.is_finite()));
+    assert!((y.data[2] - 2f32.ln()).abs() < 1e-6);
+    assert!((y.data[4] - 50.0).abs() < 1e-5);
+    assert!((y.data[5] - 100.0).abs() < 1e-5);
+    assert!(y.data[0] >= 0.0 && y.data[0] < 1e-30);
+    assert_eq!(activations::softplus_grad(&x), activations::sigmoid(&x));
+
+    let h = 1e-2;
+    let up = activations::softplus(&x.map(|v| v + h));
+    let down = activations::softplus(&x.map(|v| v - h));
+    let grad = activations::softplus_grad(&x);
+    for i in 1..4 {
+        let fd = (up.data[i] - down.data[i]) / (2.0 * h);
+        assert!(
+            (grad.data[i] - fd).abs() < 1e-3,
+            "{} vs {}",
+            grad.data[i],
+            fd
+        );
+    }
+}
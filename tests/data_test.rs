@@ -0,0 +1,224 @@
+use neurox::Tensor;
+use neurox::data::{self, RunningStats};
+
+#[test]
+fn running_stats_match_batch_statistics() {
+    let t = Tensor::from_data(
+        (0..30)
+            .map(|v| 1000.0 + (v as f32) * 0.5 + (v % 3) as f32)
+            .collect(),
+        10,
+        3,
+    );
+
+    let mut stats = RunningStats::new();
+    let (head, tail) = data::train_test_split(&t, 0.3).expect("split failed");
+    stats.update(&head);
+    stats.update(&tail);
+    assert_eq!(stats.count(), 10);
+
+    let (mean, std) = stats.finalize();
+    for j in 0..3 {
+        let col: Vec<f64> = (0..10).map(|i| t.get(i, j) as f64).collect();
+        let m = col.iter().sum::<f64>() / 10.0;
+        let var = col.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / 10.0;
+        assert!((mean.get(0, j) as f64 - m).abs() < 1e-3);
+        assert!((std.get(0, j) as f64 - var.sqrt()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn class_weights_favor_rare_class() {
+    // 6 samples of class 0, 2 of class 1, none of class 2.
+    let mut data = Vec::new();
+    for i in 0..8 {
+        data.extend(if i < 6 {
+            [1.0, 0.0, 0.0]
+        } else {
+            [0.0, 1.0, 0.0]
+        });
+    }
+    let y = Tensor::from_data(data, 8, 3);
+    let w = data::class_weights(&y);
+    assert_eq!(w.shape(), (1, 3));
+    assert!((w.data[0] - 8.0 / 18.0).abs() < 1e-6);
+    assert!((w.data[1] - 8.0 / 6.0).abs() < 1e-6);
+    assert!(w.data[1] > w.data[0]);
+    assert_eq!(w.data[2], 0.0);
+
+    let probs = Tensor::from_data([0.5, 0.3, 0.2].repeat(8), 8, 3);
+    let ones = Tensor::from_data(vec![1.0; 3], 1, 3);
+    let (plain, _) = neurox::loss::cross_entropy_loss(&probs, &y);
+    let (weighted, _) = neurox::loss::weighted_cross_entropy_loss(&probs, &y, &ones);
+    assert!((plain - weighted).abs() < 1e-5);
+}
+
+#[test]
+fn replay_buffer_evicts_oldest_and_samples() {
+    let mut buf = data::ReplayBuffer::new(3);
+    assert!(buf.is_empty());
+    for step in 0..5 {
+        let s = Tensor::from_data(vec![step as f32, 0.0], 1, 2);
+        let next = Tensor::from_data(vec![step as f32 + 1.0, 0.0], 1, 2);
+        buf.push(&s, step, step as f32 * 10.0, &next)
+            .expect("push failed");
+    }
+    assert_eq!(buf.len(), 3);
+
+    let batch = buf.sample(3, 42).expect("sample failed");
+    assert_eq!(batch.states.shape(), (3, 2));
+    assert_eq!(batch.rewards.shape(), (3, 1));
+    let mut actions = batch.actions.clone();
+    actions.sort();
+    assert_eq!(actions, vec![2, 3, 4]);
+    for (i, &a) in batch.actions.iter().enumerate() {
+        assert_eq!(batch.states.get(i, 0), a as f32);
+        assert_eq!(batch.next_states.get(i, 0), a as f32 + 1.0);
+        assert_eq!(batch.rewards.get(i, 0), a as f32 * 10.0);
+    }
+
+    let again = buf.sample(2, 42).expect("sample failed");
+    assert_eq!(again.actions, buf.sample(2, 42).unwrap().actions);
+    assert!(buf.sample(4, 0).is_err());
+    let wide = Tensor::zeros(1, 3);
+    assert!(buf.push(&wide, 0, 0.0, &wide).is_err());
+}
+
+#[test]
+fn synthetic_datasets_are_seeded() {
+    let (x, y) = data::make_classification(30, 4, 3, 9);
+    assert_eq!(x.shape(), (30, 4));
+    assert_eq!(y.shape(), (30, 3));
+    assert!((0..30).all(|i| (0..3).map(|j| y.get(i, j)).sum::<f32>() == 1.0));
+    let (x2, y2) = data::make_classification(30, 4, 3, 9);
+    assert_eq!(x.data, x2.data);
+    assert_eq!(y.data, y2.data);
+    assert_ne!(data::make_classification(30, 4, 3, 10).0.data, x.data);
+
+    let (xr, yr) = data::make_regression(20, 5, 0.1, 4);
+    assert_eq!(xr.shape(), (20, 5));
+    assert_eq!(yr.shape(), (20, 1));
+    let (xr2, yr2) = data::make_regression(20, 5, 0.1, 4);
+    assert_eq!(xr.data, xr2.data);
+    assert_eq!(yr.data, yr2.data);
+}
+
+#[test]
+fn to_csv_round_trips() {
+    let t = Tensor::from_data(vec![1.5, -0.1, 3.0, 1.0e-7, 2.0 / 3.0, -42.0], 2, 3);
+    let dir = std::env::temp_dir();
+    let exact = dir.join(format!("neurox_to_csv_{}.csv", std::process::id()));
+    let exact = exact.to_str().unwrap();
+    t.to_csv(exact).expect("write failed");
+    let back = data::tensor_from_csv(exact).expect("read failed");
+    std::fs::remove_file(exact).ok();
+    assert_eq!(back.shape(), (2, 3));
+    assert_eq!(back.data, t.data);
+
+    let rounded = dir.join(format!("neurox_to_csv_p_{}.csv", std::process::id()));
+    let rounded = rounded.to_str().unwrap();
+    t.to_csv_with_precision(rounded, 3).expect("write failed");
+    let text = std::fs::read_to_string(rounded).expect("read failed");
+    let back = data::tensor_from_csv(rounded).expect("read failed");
+    std::fs::remove_file(rounded).ok();
+    assert!(text.starts_with("1.500,-0.100,3.000\n"));
+    assert!(
+        back.data
+            .iter()
+            .zip(&t.data)
+            .all(|(a, b)| (a - b).abs() <= 5e-4)
+    );
+
+    assert!(t.to_csv("/nonexistent-dir/out.csv").is_err());
+}
+
+#[test]
+fn standard_scaler_uses_train_statistics() {
+    use neurox::errors::NeuroxError;
+
+    let train = Tensor::from_data(vec![1.0, 5.0, 3.0, 5.0, 5.0, 5.0], 3, 2);
+    let test = Tensor::from_data(vec![7.0, 5.0, 3.0, 6.0], 2, 2);
+
+    let mut scaler = data::StandardScaler::new();
+    assert!(matches!(
+        scaler.transform(&test),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+    let scaled_train = scaler.fit_transform(&train).expect("fit failed");
+    let std = (8.0f32 / 3.0).sqrt();
+    assert!((scaled_train.get(0, 0) + 2.0 / std).abs() < 1e-6);
+    // The constant column is centered but not divided by zero.
+    assert_eq!(scaled_train.get(1, 1), 0.0);
+
+    let scaled_test = scaler.transform(&test).expect("transform failed");
+    assert!((scaled_test.get(0, 0) - 4.0 / std).abs() < 1e-6);
+    assert_eq!(scaled_test.get(1, 1), 1.0);
+
+    assert!(matches!(
+        scaler.transform(&Tensor::zeros(1, 3)),
+        Err(NeuroxError::ShapeMismatch(_))
+    ));
+}
+
+#[test]
+fn pca_recovers_line_direction() {
+    // Points on the line through (1, 2, 3) with direction (2, -1, 2) / 3.
+    let dir = [2.0 / 3.0, -1.0 / 3.0, 2.0 / 3.0];
+    let mut values = Vec::new();
+    for s in [-2.0f32, -0.5, 0.0, 1.0, 1.5, 3.0] {
+        values.extend([1.0 + s * dir[0], 2.0 + s * dir[1], 3.0 + s * dir[2]]);
+    }
+    let t = Tensor::from_data(values, 6, 3);
+
+    let components = data::pca_fit(&t, 2).expect("pca_fit failed");
+    assert_eq!(components.shape(), (3, 2));
+    for (r, d) in dir.iter().enumerate() {
+        assert!((components.get(r, 0) - d).abs() < 1e-4);
+    }
+    let dot: f32 = (0..3)
+        .map(|r| components.get(r, 0) * components.get(r, 1))
+        .sum();
+    assert!(dot.abs() < 1e-4);
+
+    let mean = Tensor::from_data(vec![1.0, 2.0, 3.0], 1, 3);
+    let centered = t.sub_row_broadcast(&mean).unwrap();
+    let first = data::pca_fit(&t, 1).unwrap();
+    let scores = data::pca_transform(&centered, &first).expect("transform failed");
+    assert_eq!(scores.shape(), (6, 1));
+    assert!((scores.get(0, 0) + 2.0).abs() < 1e-4);
+
+    assert!(data::pca_fit(&t, 4).is_err());
+    assert!(data::pca_transform(&Tensor::zeros(2, 2), &first).is_err());
+}
+
+#[test]
+fn stratified_folds_preserve_class_proportions() {
+    // 12 samples of class 0, 6 of class 1 and 3 of class 2.
+    let labels: Vec<usize> = [0]
+        .repeat(12)
+        .into_iter()
+        .chain([1].repeat(6))
+        .chain([2].repeat(3))
+        .collect();
+    let mut y = Tensor::zeros(labels.len(), 3);
+    for (i, &c) in labels.iter().enumerate() {
+        y.set(i, c, 1.0);
+    }
+
+    let folds = data::stratified_k_fold_indices(&y, 3, 7).expect("folding failed");
+    assert_eq!(folds.len(), 3);
+    let mut seen = vec![0; labels.len()];
+    for (train, val) in &folds {
+        assert_eq!(train.len() + val.len(), labels.len());
+        assert!(train.iter().all(|i| !val.contains(i)));
+        let count = |c: usize| val.iter().filter(|&&i| labels[i] == c).count();
+        assert_eq!((count(0), count(1), count(2)), (4, 2, 1));
+        val.iter().for_each(|&i| seen[i] += 1);
+    }
+    assert!(seen.iter().all(|&n| n == 1));
+
+    assert_eq!(data::stratified_k_fold_indices(&y, 3, 7).unwrap(), folds);
+    assert_ne!(data::stratified_k_fold_indices(&y, 3, 8).unwrap(), folds);
+    assert!(data::stratified_k_fold_indices(&y, 1, 7).is_err());
+    assert!(data::stratified_k_fold_indices(&y, 22, 7).is_err());
+}
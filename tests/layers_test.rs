@@ -0,0 +1,289 @@
+use neurox::layers::{Activation, Dense};
+use neurox::{Tensor, activations, ops};
+
+#[test]
+fn fused_forward_matches_staged_computation() {
+    let x = Tensor::random(5, 4);
+    for act in [
+        Activation::ReLU,
+        Activation::LeakyReLU(0.1),
+        Activation::ELU(1.0),
+        Activation::GELU,
+        Activation::Softplus,
+        Activation::Softmax,
+        Activation::Sigmoid,
+        Activation::Tanh,
+        Activation::None,
+    ] {
+        let mut layer = Dense::new(4, 3, act);
+        let out = layer.forward(&x).expect("forward failed");
+
+        let z = ops::matmul(&x, &layer.w)
+            .and_then(|z| z.add_row_broadcast(&layer.b))
+            .expect("staged forward failed");
+        let expected = match act {
+            Activation::ReLU => activations::relu(&z),
+            Activation::LeakyReLU(alpha) => activations::leaky_relu(&z, alpha),
+            Activation::ELU(alpha) => activations::elu(&z, alpha),
+            Activation::GELU => activations::gelu(&z),
+            Activation::Softplus => activations::softplus(&z),
+            Activation::Softmax => activations::softmax(&z),
+            Activation::Sigmoid => activations::sigmoid(&z),
+            Activation::Tanh => activations::tanh(&z),
+            Activation::None => z,
+        };
+        assert_eq!(out.shape(), expected.shape());
+        for (a, b) in out.data.iter().zip(&expected.data) {
+            assert!((a - b).abs() < 1e-6, "{:?}: {} vs {}", act, a, b);
+        }
+    }
+}
+
+#[test]
+fn init_variances_match_theory() {
+    use neurox::layers::Init;
+
+    neurox::utils::set_seed(42);
+    let (fan_in, fan_out) = (100, 200);
+    let cases = [
+        (Init::HeUniform, 2.0 / fan_in as f32),
+        (Init::LecunNormal, 1.0 / fan_in as f32),
+        (Init::HeNormal, 2.0 / fan_in as f32),
+        (Init::Xavier, 2.0 / (fan_in + fan_out) as f32),
+    ];
+    for (init, expected) in cases {
+        let layer = Dense::with_init(fan_in, fan_out, Activation::ReLU, init);
+        let n = layer.w.data.len() as f32;
+        let mean = layer.w.data.iter().sum::<f32>() / n;
+        let var = layer.w.data.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / n;
+        assert!(mean.abs() < 0.01, "{:?}: mean {}", init, mean);
+        assert!(
+            (var - expected).abs() / expected < 0.05,
+            "{:?}: variance {} vs {}",
+            init,
+            var,
+            expected
+        );
+        assert!(layer.b.data.iter().all(|&b| b == 0.0));
+    }
+}
+
+#[test]
+fn group_norm_gradients_match_finite_differences() {
+    use neurox::layers::{GroupNorm, Layer};
+
+    let x = Tensor::from_data(vec![0.3, -1.2, 0.8, 2.0, 1.5, 0.1, -0.4, 0.9], 2, 4);
+    // Weights defining the scalar loss L = sum(r * y).
+    let r = Tensor::from_data(vec![0.5, -1.0, 2.0, 0.3, -0.7, 1.1, 0.4, -1.5], 2, 4);
+    let mut gn = GroupNorm::new(4, 2);
+    gn.gamma = Tensor::from_data(vec![1.5, -0.5, 0.8, 2.0], 1, 4);
+    gn.beta = Tensor::from_data(vec![0.1, 0.2, -0.3, 0.0], 1, 4);
+
+    let loss = |gn: &mut GroupNorm, x: &Tensor| -> f32 {
+        let y = gn.forward(x).expect("forward failed");
+        y.data.iter().zip(&r.data).map(|(a, b)| a * b).sum()
+    };
+    loss(&mut gn, &x);
+    let dx = gn.backward(&r).expect("backward failed");
+    let dgamma = gn.grad_gamma.clone().expect("missing grad_gamma");
+
+    let h = 1e-3;
+    for idx in 0..x.data.len() {
+        let mut plus = x.clone();
+        plus.data[idx] += h;
+        let mut minus = x.clone();
+        minus.data[idx] -= h;
+        let numeric = (loss(&mut gn, &plus) - loss(&mut gn, &minus)) / (2.0 * h);
+        assert!(
+            (numeric - dx.data[idx]).abs() < 2e-2,
+            "dx[{}]: {} vs {}",
+            idx,
+            numeric,
+            dx.data[idx]
+        );
+    }
+    for j in 0..4 {
+        let orig = gn.gamma.data[j];
+        gn.gamma.data[j] = orig + h;
+        let up = loss(&mut gn, &x);
+        gn.gamma.data[j] = orig - h;
+        let down = loss(&mut gn, &x);
+        gn.gamma.data[j] = orig;
+        let numeric = (up - down) / (2.0 * h);
+        assert!((numeric - dgamma.data[j]).abs() < 1e-2);
+    }
+    assert_eq!(gn.num_params(), 8);
+}
+
+#[test]
+fn column_dropout_zeros_whole_columns() {
+    use neurox::layers::Dropout;
+
+    neurox::utils::set_seed(3);
+    let mut dropout = Dropout::columns(0.5);
+    let x = Tensor::from_data(vec![1.0; 4 * 16], 4, 16);
+    let out = dropout.forward(&x);
+    let grad = dropout.backward(&x).expect("backward failed");
+
+    let mut dropped = 0;
+    for j in 0..16 {
+        let v = out.get(0, j);
+        assert!(v == 0.0 || v == 2.0);
+        for i in 1..4 {
+            assert_eq!(out.get(i, j), v);
+        }
+        for i in 0..4 {
+            assert_eq!(grad.get(i, j), v);
+        }
+        if v == 0.0 {
+            dropped += 1;
+        }
+    }
+    assert!(dropped > 0 && dropped < 16);
+}
+
+#[test]
+fn spectral_norm_keeps_weight_norm_near_one() {
+    use neurox::layers::{Layer, SpectralNorm};
+
+    neurox::utils::set_seed(8);
+    let mut dense = Dense::new(4, 3, Activation::None);
+    dense.w = dense.w.map(|v| v * 5.0);
+    let raw_norm = dense.w.spectral_norm(100);
+    let mut sn = SpectralNorm::new(dense);
+
+    let x = Tensor::random(6, 4);
+    for _ in 0..10 {
+        let out = sn.forward(&x).expect("forward failed");
+        sn.backward(&out).expect("backward failed");
+    }
+    assert!((sn.sigma() - raw_norm).abs() < 1e-2 * raw_norm);
+    assert!((sn.weight().spectral_norm(100) - 1.0).abs() < 1e-2);
+    assert_eq!(sn.num_params(), 4 * 3 + 3);
+}
+
+#[test]
+fn weight_norm_gradients_match_finite_differences() {
+    use neurox::layers::{Layer, WeightNorm};
+
+    neurox::utils::set_seed(4);
+    let mut wn = WeightNorm::new(Dense::new(3, 2, Activation::Tanh));
+    wn.g = Tensor::from_data(vec![0.7, -1.3], 1, 2);
+    let x = Tensor::from_data(vec![0.5, -0.2, 0.9, -1.0, 0.4, 0.3], 2, 3);
+    let r = Tensor::from_data(vec![1.0, -0.5, 0.3, 2.0], 2, 2);
+
+    let loss = |wn: &mut WeightNorm| -> f32 {
+        let y = wn.forward(&x).expect("forward failed");
+        y.data.iter().zip(&r.data).map(|(a, b)| a * b).sum()
+    };
+    loss(&mut wn);
+    wn.backward(&r).expect("backward failed");
+    let grad_v = wn.grad_v.clone().unwrap();
+    let grad_g = wn.grad_g.clone().unwrap();
+
+    let h = 1e-3;
+    let v0 = wn.v.clone();
+    for idx in 0..v0.data.len() {
+        wn.v = v0.perturbed(idx, h);
+        let up = loss(&mut wn);
+        wn.v = v0.perturbed(idx, -h);
+        let down = loss(&mut wn);
+        let numeric = (up - down) / (2.0 * h);
+        assert!(
+            (numeric - grad_v.data[idx]).abs() < 1e-2,
+            "dv[{}]: {} vs {}",
+            idx,
+            numeric,
+            grad_v.data[idx]
+        );
+    }
+    wn.v = v0;
+    let g0 = wn.g.clone();
+    for idx in 0..2 {
+        wn.g = g0.perturbed(idx, h);
+        let up = loss(&mut wn);
+        wn.g = g0.perturbed(idx, -h);
+        let down = loss(&mut wn);
+        let numeric = (up - down) / (2.0 * h);
+        assert!((numeric - grad_g.data[idx]).abs() < 1e-2);
+    }
+    assert_eq!(wn.num_params(), 6 + 2 + 2);
+}
+
+#[test]
+fn from_weights_uses_given_parameters() {
+    let w = Tensor::from_data(vec![1.0, -1.0, 2.0, 0.5, 0.0, 3.0], 3, 2);
+    let b = Tensor::from_data(vec![0.5, -4.0], 1, 2);
+    let mut layer = Dense::from_weights(w, b, Activation::ReLU).expect("construction failed");
+    assert!(layer.grad_w.is_none() && layer.preact().is_none());
+
+    let x = Tensor::from_data(vec![1.0, 2.0, 3.0, -1.0, 0.0, 1.0], 2, 3);
+    let out = layer.forward(&x).expect("forward failed");
+    // Row 0: [1 + 4 + 0 + 0.5, -1 + 1 + 9 - 4] = [5.5, 5]; row 1: [-1 + 0.5, 1 + 3 - 4] -> ReLU.
+    assert_eq!(out.data, vec![5.5, 5.0, 0.0, 0.0]);
+
+    let bad = Dense::from_weights(Tensor::zeros(3, 2), Tensor::zeros(2, 1), Activation::None);
+    assert!(matches!(bad, Err(neurox::NeuroxError::ShapeMismatch(_))));
+}
+
+#[test]
+fn softmax_layer_gradients_match_finite_differences() {
+    neurox::utils::set_seed(14);
+    let x = Tensor::random(3, 4);
+    // Loss L = sum(c * softmax(xW + b)) for fixed weights c, so dL/d out = c.
+    let c = Tensor::random(3, 3);
+    let mut layer = Dense::new(4, 3, Activation::Softmax);
+    let loss = |layer: &mut Dense| -> f32 {
+        let out = layer.forward(&x).unwrap();
+        out.data.iter().zip(&c.data).map(|(o, c)| o * c).sum()
+    };
+
+    let out = layer.forward(&x).unwrap();
+    out.assert_probability_rows(1e-5)
+        .expect("rows are not distributions");
+    let grad_x = layer.backward(&c).unwrap();
+    let grad_w = layer.grad_w.clone().unwrap();
+    let grad_b = layer.grad_b.clone().unwrap();
+
+    let h = 1e-2;
+    for idx in 0..layer.w.data.len() {
+        let orig = layer.w.data[idx];
+        layer.w.data[idx] = orig + h;
+        let up = loss(&mut layer);
+        layer.w.data[idx] = orig - h;
+        let down = loss(&mut layer);
+        layer.w.data[idx] = orig;
+        assert!((grad_w.data[idx] - (up - down) / (2.0 * h)).abs() < 1e-3);
+    }
+    for idx in 0..layer.b.data.len() {
+        let orig = layer.b.data[idx];
+        layer.b.data[idx] = orig + h;
+        let up = loss(&mut layer);
+        layer.b.data[idx] = orig - h;
+        let down = loss(&mut layer);
+        layer.b.data[idx] = orig;
+        assert!((grad_b.data[idx] - (up - down) / (2.0 * h)).abs() < 1e-3);
+    }
+    // Softmax is shift-invariant, so moving every bias together leaves the loss unchanged.
+    assert!(grad_b.data.iter().sum::<f32>().abs() < 1e-5);
+    assert_eq!(grad_x.shape(), (3, 4));
+}
+
+#[test]
+fn reset_parameters_resamples_and_clears_state() {
+    use neurox::layers::Init;
+
+    let mut layer = Dense::new(4, 3, Activation::ReLU);
+    let x = Tensor::random(2, 4);
+    layer.forward(&x).unwrap();
+    layer.backward(&Tensor::random(2, 3)).unwrap();
+    assert!(layer.preact().is_some() && layer.grad_w.is_some());
+    let before = layer.w.clone();
+
+    layer.reset_parameters(Init::HeNormal);
+    assert_ne!(layer.w, before);
+    assert_eq!(layer.w.shape(), (4, 3));
+    assert_eq!(layer.b, Tensor::zeros(1, 3));
+    assert!(layer.preact().is_none());
+    assert!(layer.grad_w.is_none() && layer.grad_b.is_none());
+}
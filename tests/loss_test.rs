@@ -0,0 +1,104 @@
+use neurox::Tensor;
+use neurox::{activations, loss};
+
+#[test]
+fn cross_entropy_gradient_with_soft_targets() {
+    let logits = Tensor::from_data(vec![0.3, -1.2, 2.0, 0.5], 2, 2);
+    let target = Tensor::from_data(vec![0.7, 0.3, 0.25, 0.75], 2, 2);
+
+    let ce = |z: &Tensor| loss::cross_entropy_loss(&activations::softmax(z), &target).0;
+    let (loss_value, grad) = loss::cross_entropy_loss(&activations::softmax(&logits), &target);
+    assert!((loss_value - ce(&logits)).abs() < 1e-6);
+
+    // gradient of the batch-mean loss w.r.t. the logits, by central differences
+    let eps = 1e-2;
+    let batch = logits.rows as f32;
+    for idx in 0..logits.data.len() {
+        let mut plus = logits.clone();
+        plus.data[idx] += eps;
+        let mut minus = logits.clone();
+        minus.data[idx] -= eps;
+        let numeric = (ce(&plus) - ce(&minus)) / (2.0 * eps) / batch;
+        assert!(
+            (numeric - grad.data[idx]).abs() < 1e-3,
+            "index {}: numeric {} vs analytic {}",
+            idx,
+            numeric,
+            grad.data[idx]
+        );
+    }
+}
+
+#[test]
+fn mse_reductions() {
+    use neurox::loss::MseReduction;
+
+    let pred = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    let target = Tensor::from_data(vec![0.0, 2.0, 1.0, 4.0, 8.0, 6.0], 2, 3);
+    // squared errors: 1, 0, 4, 0, 9, 0 -> sum 14
+
+    let (per_row, g_row) = loss::mse_loss_with(&pred, &target, MseReduction::PerRow);
+    assert!((per_row - 7.0).abs() < 1e-6);
+    assert!((g_row.get(0, 0) - 1.0).abs() < 1e-6); // 2 * 1 / 2
+
+    let (per_elem, g_elem) = loss::mse_loss_with(&pred, &target, MseReduction::PerElement);
+    assert!((per_elem - 14.0 / 6.0).abs() < 1e-6);
+    assert!((g_elem.get(1, 1) - (-1.0)).abs() < 1e-6); // 2 * -3 / 6
+
+    let (default_loss, _) = loss::mse_loss(&pred, &target);
+    assert_eq!(default_loss, per_row);
+}
+
+#[test]
+fn sequence_cross_entropy_ignores_padding() {
+    const PAD: usize = 0;
+    let logits = Tensor::from_data(vec![2.0, 0.5, -1.0, 0.1, 0.2, 3.0, 1.0, 1.0, 1.0], 3, 3);
+    let labels = [1, 2, PAD];
+    let (loss, grad) = loss::sequence_cross_entropy(&logits, &labels, PAD);
+
+    assert!(grad.data[6..].iter().all(|&g| g == 0.0));
+    let probs = activations::softmax(&logits);
+    let expected = -(probs.get(0, 1).ln() + probs.get(1, 2).ln()) / 2.0;
+    assert!((loss - expected).abs() < 1e-5);
+    assert!((grad.get(0, 1) - (probs.get(0, 1) - 1.0) / 2.0).abs() < 1e-6);
+    assert!((grad.get(1, 0) - probs.get(1, 0) / 2.0).abs() < 1e-6);
+
+    let (none, zero) = loss::sequence_cross_entropy(&logits, &[PAD; 3], PAD);
+    assert_eq!(none, 0.0);
+    assert!(zero.data.iter().all(|&g| g == 0.0));
+}
+
+#[test]
+fn cross_entropy_eps_clamps_zero_probability() {
+    let prob = Tensor::from_data(vec![0.0, 1.0], 1, 2);
+    let target = Tensor::from_data(vec![1.0, 0.0], 1, 2);
+
+    let (default, _) = loss::cross_entropy_loss(&prob, &target);
+    assert!((default + neurox::constants::LOG_EPS.ln()).abs() < 1e-4);
+
+    let (loose, grad) = loss::cross_entropy_loss_with_eps(&prob, &target, 1e-3);
+    assert!((loose + 1e-3f32.ln()).abs() < 1e-4);
+    assert!(loose < default);
+    assert!((grad.get(0, 0) - (1e-3 - 1.0)).abs() < 1e-6);
+}
+
+#[test]
+fn log_softmax_stays_finite_where_naive_path_underflows() {
+    let logits = Tensor::from_data(vec![1000.0, 0.0, 0.5, -0.5], 2, 2);
+    let log_p = activations::log_softmax(&logits);
+    assert!(log_p.data.iter().all(|v| v.is_finite()));
+    assert_eq!(log_p.data[0], 0.0);
+    assert_eq!(log_p.data[1], -1000.0);
+    let naive = activations::softmax(&logits).map(f32::ln);
+    assert_eq!(naive.data[1], f32::NEG_INFINITY);
+
+    let target = Tensor::from_data(vec![0.0, 1.0, 1.0, 0.0], 2, 2);
+    let (nll, grad) = loss::nll_loss(&log_p, &target);
+    // The confidently wrong first row keeps its full loss of 1000 instead of being clamped.
+    assert!((nll - (1000.0 + (-1f32).exp().ln_1p())).abs() < 1e-3);
+    let (ce, ce_grad) = loss::cross_entropy_loss(&activations::softmax(&logits), &target);
+    assert!(ce < 20.0);
+    for (a, b) in grad.data.iter().zip(&ce_grad.data) {
+        assert!((a - b).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,927 @@
+use neurox::layers::Activation;
+use neurox::{Model, Tensor};
+
+#[test]
+fn multilabel_accuracy_thresholds_each_output() {
+    // single linear layer: identity weights, zero bias, so logits == inputs
+    let mut model = Model::new(&[3, 3], Activation::None);
+    model.layers[0].w = Tensor::from_data(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 3, 3);
+    model.layers[0].b = Tensor::zeros(1, 3);
+
+    let x = Tensor::from_data(vec![2.0, -2.0, 1.0, -1.0, 3.0, -3.0], 2, 3);
+    // predictions: [1, 0, 1], [0, 1, 0]; one wrong label in each row
+    let y = Tensor::from_data(vec![1.0, 1.0, 1.0, 0.0, 1.0, 1.0], 2, 3);
+
+    let acc = model
+        .multilabel_accuracy(&x, &y, 0.5)
+        .expect("accuracy failed");
+    assert!((acc - 4.0 / 6.0).abs() < 1e-6);
+
+    let bad = Tensor::zeros(2, 2);
+    assert!(model.multilabel_accuracy(&x, &bad, 0.5).is_err());
+}
+
+#[test]
+fn feature_importance_ignores_unused_feature() {
+    let mut model = Model::new(&[3, 4, 2], Activation::Tanh);
+    // cut every connection from input feature 1
+    for j in 0..4 {
+        model.layers[0].w.set(1, j, 0.0);
+    }
+
+    let x = Tensor::random(8, 3);
+    let mut y = Tensor::zeros(8, 2);
+    for i in 0..8 {
+        y.set(i, i % 2, 1.0);
+    }
+
+    let imp = model.feature_importance(&x, &y).expect("importance failed");
+    assert_eq!(imp.shape(), (1, 3));
+    assert!(imp.get(0, 1).abs() < 1e-7);
+    assert!(imp.get(0, 0) > 0.0);
+    assert!(imp.get(0, 2) > 0.0);
+}
+
+#[test]
+fn pruned_weights_stay_zero_through_training() {
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+
+    for use_adam in [false, true] {
+        let mut model = Model::new(&[2, 8, 2], Activation::ReLU);
+        model.prune(0.5);
+        let zeros = |m: &Model| -> Vec<usize> {
+            m.layers
+                .iter()
+                .map(|l| l.w.data.iter().filter(|&&v| v == 0.0).count())
+                .collect()
+        };
+        assert_eq!(zeros(&model), vec![8, 8]);
+
+        if use_adam {
+            model
+                .train_adam(&x, &y, 20, 4, 0.01)
+                .expect("training failed");
+        } else {
+            model
+                .train_sgd(&x, &y, 20, 4, 0.1)
+                .expect("training failed");
+        }
+        for (l, count) in model.layers.iter().zip(zeros(&model)) {
+            let mask = l.mask.as_ref().expect("mask missing");
+            assert!(count >= 8);
+            assert!(
+                l.w.data
+                    .iter()
+                    .zip(mask)
+                    .all(|(&w, &keep)| keep || w == 0.0)
+            );
+        }
+    }
+}
+
+#[test]
+fn accuracy_skips_ignored_class() {
+    let mut model = Model::new(&[3, 3], Activation::None);
+    model.layers[0].w = Tensor::from_data(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 3, 3);
+    model.layers[0].b = Tensor::zeros(1, 3);
+
+    // predicted classes: 0, 1, 2, 1
+    let x = Tensor::from_data(
+        vec![5.0, 0.0, 0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 5.0, 0.0, 5.0, 0.0],
+        4,
+        3,
+    );
+    // true classes: 0, 1, 1, 2 (the last sample uses the ignored class 2)
+    let y = Tensor::from_data(
+        vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        4,
+        3,
+    );
+
+    let all = model.accuracy(&x, &y, None).expect("accuracy failed");
+    assert!((all - 0.5).abs() < 1e-6);
+    let ignored = model.accuracy(&x, &y, Some(2)).expect("accuracy failed");
+    assert!((ignored - 2.0 / 3.0).abs() < 1e-6);
+    assert!(model.accuracy(&x, &y, Some(3)).is_err());
+}
+
+#[test]
+fn output_layer_is_linear() {
+    let model = Model::new(&[3, 5, 4, 2], Activation::ReLU);
+    assert_eq!(model.layers.len(), 3);
+    assert!(matches!(model.layers[0].activation, Activation::ReLU));
+    assert!(matches!(model.layers[1].activation, Activation::ReLU));
+    assert!(matches!(model.layers[2].activation, Activation::None));
+
+    // a linear output layer can produce negative logits
+    let mut model = Model::new(&[2, 1], Activation::ReLU);
+    model.layers[0].w = Tensor::from_data(vec![-1.0, -1.0], 2, 1);
+    model.layers[0].b = Tensor::zeros(1, 1);
+    let out = model
+        .forward(&Tensor::from_data(vec![1.0, 1.0], 1, 2))
+        .expect("forward failed");
+    assert_eq!(out.get(0, 0), -2.0);
+}
+
+#[test]
+fn jacobian_matches_finite_differences() {
+    let mut model = Model::new(&[3, 5, 2], Activation::Tanh);
+    let x = Tensor::from_data(vec![0.2, -0.4, 0.7], 1, 3);
+    let jac = model.jacobian(&x).expect("jacobian failed");
+    assert_eq!(jac.shape(), (2, 3));
+
+    let eps = 1e-2;
+    for j in 0..3 {
+        let mut plus = x.clone();
+        plus.data[j] += eps;
+        let mut minus = x.clone();
+        minus.data[j] -= eps;
+        let out_plus = model.forward(&plus).unwrap();
+        let out_minus = model.forward(&minus).unwrap();
+        for k in 0..2 {
+            let numeric = (out_plus.get(0, k) - out_minus.get(0, k)) / (2.0 * eps);
+            assert!(
+                (numeric - jac.get(k, j)).abs() < 1e-3,
+                "d out_{} / d x_{}: numeric {} vs analytic {}",
+                k,
+                j,
+                numeric,
+                jac.get(k, j)
+            );
+        }
+    }
+
+    assert!(model.jacobian(&Tensor::zeros(2, 3)).is_err());
+}
+
+#[test]
+fn from_configs_builds_dense_and_dropout() {
+    use neurox::layers::LayerConfig;
+
+    let configs = [
+        LayerConfig {
+            size: 8,
+            activation: Activation::ReLU,
+            dropout: Some(0.5),
+        },
+        LayerConfig {
+            size: 3,
+            activation: Activation::None,
+            dropout: None,
+        },
+    ];
+    let mut model = Model::from_configs(4, &configs).expect("valid configs");
+    assert_eq!(model.layers.len(), 2);
+    assert_eq!(model.layers[0].w.shape(), (4, 8));
+    assert!(matches!(model.layers[0].activation, Activation::ReLU));
+    assert_eq!(model.layers[0].dropout.as_ref().map(|d| d.rate), Some(0.5));
+    assert!(matches!(model.layers[1].activation, Activation::None));
+    assert!(model.layers[1].dropout.is_none());
+
+    let x = Tensor::random(5, 4);
+    assert_eq!(model.forward(&x).expect("forward failed").shape(), (5, 3));
+
+    // inference mode is deterministic
+    model.set_training(false);
+    let a = model.forward(&x).unwrap();
+    let b = model.forward(&x).unwrap();
+    assert_eq!(a.data, b.data);
+
+    let bad = [LayerConfig {
+        size: 3,
+        activation: Activation::None,
+        dropout: Some(1.0),
+    }];
+    assert!(Model::from_configs(4, &bad).is_err());
+}
+
+#[test]
+fn metrics_and_gradients_ignore_dropout() {
+    use neurox::layers::Dropout;
+
+    let mut model = Model::new(&[4, 16, 3], Activation::ReLU);
+    model.layers[0].dropout = Some(Dropout::new(0.5));
+    let x = Tensor::random(8, 4);
+    let y = Tensor::from_data(
+        (0..8)
+            .flat_map(|i| (0..3).map(move |j| (i % 3 == j) as u8 as f32))
+            .collect(),
+        8,
+        3,
+    );
+
+    let a = model.accuracy(&x, &y, None).expect("accuracy failed");
+    let b = model.accuracy(&x, &y, None).expect("accuracy failed");
+    assert_eq!(a, b);
+    let a = model.multilabel_accuracy(&x, &y, 0.5).unwrap();
+    assert_eq!(a, model.multilabel_accuracy(&x, &y, 0.5).unwrap());
+    let g = model.input_gradient(&x, &y).unwrap();
+    assert_eq!(g, model.input_gradient(&x, &y).unwrap());
+    let row = x.slice_rows(0, 1).unwrap();
+    let j = model.jacobian(&row).unwrap();
+    assert_eq!(j, model.jacobian(&row).unwrap());
+    // the layer is left in training mode
+    assert!(model.layers[0].dropout.as_ref().unwrap().training);
+}
+
+#[test]
+fn profile_forward_reports_each_layer() {
+    let mut model = Model::new(&[6, 16, 8, 3], Activation::ReLU);
+    let timings = model
+        .profile_forward(&Tensor::random(10, 6))
+        .expect("profile failed");
+    let indices: Vec<usize> = timings.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+}
+
+#[test]
+fn confusion_accumulates_across_batches() {
+    use neurox::model::ConfusionAccumulator;
+
+    let preds = [0, 1, 2, 2, 1, 0, 1];
+    let targets = [0, 1, 1, 2, 0, 0, 2];
+
+    let mut streamed = ConfusionAccumulator::new(3);
+    streamed.update(&preds[..4], &targets[..4]);
+    streamed.update(&preds[4..], &targets[4..]);
+
+    let mut whole = ConfusionAccumulator::new(3);
+    whole.update(&preds, &targets);
+
+    assert_eq!(streamed.matrix().data, whole.matrix().data);
+    let m = whole.matrix();
+    assert_eq!(m.get(0, 0), 2.0);
+    assert_eq!(m.get(1, 2), 1.0);
+    assert_eq!(m.get(2, 1), 1.0);
+    assert_eq!(m.data.iter().sum::<f32>(), 7.0);
+}
+
+#[test]
+fn mc_dropout_has_variance_but_predict_proba_is_deterministic() {
+    use neurox::layers::Dropout;
+
+    let mut model = Model::new(&[4, 16, 3], Activation::ReLU);
+    model.layers[0].dropout = Some(Dropout::new(0.5));
+    let x = Tensor::random(6, 4);
+
+    let (mean, var) = model.predict_proba_mc(&x, 20).expect("mc failed");
+    assert_eq!(mean.shape(), (6, 3));
+    assert!(var.data.iter().any(|&v| v > 0.0));
+    for i in 0..6 {
+        let sum: f32 = (0..3).map(|j| mean.get(i, j)).sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+    }
+
+    let a = model.predict_proba(&x).expect("predict failed");
+    let b = model.predict_proba(&x).expect("predict failed");
+    assert_eq!(a.data, b.data);
+    // the layer's own mode is restored afterwards
+    assert!(model.layers[0].dropout.as_ref().unwrap().training);
+    assert!(model.predict_proba_mc(&x, 0).is_err());
+}
+
+#[test]
+fn per_sample_loss_flags_wrong_label() {
+    let mut model = Model::new(&[2, 2], Activation::None);
+    model.layers[0].w = Tensor::from_data(vec![4.0, 0.0, 0.0, 4.0], 2, 2);
+    model.layers[0].b = Tensor::zeros(1, 2);
+
+    let x = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0], 3, 2);
+    // the last sample is labelled class 1 although it clearly belongs to class 0
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0], 3, 2);
+
+    let losses = model.per_sample_loss(&x, &y).expect("loss failed");
+    assert_eq!(losses.len(), 3);
+    assert!(losses[2] > 10.0 * losses[0]);
+    assert!(losses[2] > 10.0 * losses[1]);
+}
+
+#[test]
+fn per_sample_loss_and_ensemble_ignore_output_activation() {
+    use neurox::model::{OutputActivation, ensemble_predict_proba};
+
+    neurox::utils::set_seed(23);
+    let mut model = Model::new(&[3, 5, 3], Activation::ReLU);
+    let x = Tensor::from_data(vec![1.0, -2.0, 0.5, 3.0, 0.1, -1.5], 2, 3);
+    let y = Tensor::from_data(vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.0], 2, 3);
+
+    let losses = model.per_sample_loss(&x, &y).unwrap();
+    let ensemble = ensemble_predict_proba(std::slice::from_mut(&mut model), &x).unwrap();
+    for activation in [OutputActivation::None, OutputActivation::Sigmoid] {
+        model.output_activation = activation;
+        assert_eq!(model.per_sample_loss(&x, &y).unwrap(), losses);
+        let probs = ensemble_predict_proba(std::slice::from_mut(&mut model), &x).unwrap();
+        assert_eq!(probs, ensemble);
+    }
+    assert!(losses.iter().all(|&l| l > 0.0));
+}
+
+#[test]
+fn warmup_ramps_learning_rate() {
+    use neurox::model::TrainConfig;
+
+    let mut config = TrainConfig::new(30, 4, 0.1);
+    config.warmup_epochs = 10;
+    assert!(config.lr_at(0) <= 0.011);
+    assert!(config.lr_at(0) > 0.0);
+    assert!(config.lr_at(4) < config.lr_at(5));
+    assert!((config.lr_at(9) - 0.1).abs() < 1e-7);
+    assert!((config.lr_at(20) - 0.1).abs() < 1e-7);
+
+    // after warmup the schedule takes over, counted from the end of warmup
+    config.schedule = Some(Box::new(|step: usize| 0.1 * 0.5f32.powi(step as i32)));
+    assert!((config.lr_at(10) - 0.1).abs() < 1e-7);
+    assert!((config.lr_at(11) - 0.05).abs() < 1e-7);
+
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let mut model = Model::new(&[2, 6, 2], Activation::ReLU);
+    let mut sgd = neurox::SGD::new(0.1);
+    let history = model
+        .train(&x, &y, &config, &mut sgd)
+        .expect("training failed");
+    assert_eq!(history.len(), 30);
+    assert!((sgd.lr - config.lr_at(29)).abs() < 1e-9);
+}
+
+#[test]
+fn seeded_training_is_bit_reproducible() {
+    neurox::utils::set_deterministic(true);
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+
+    let run = || {
+        neurox::utils::set_seed(7);
+        let mut model = Model::new(&[2, 16, 2], Activation::Tanh);
+        model
+            .train_adam(&x, &y, 30, 2, 0.01)
+            .expect("training failed");
+        model
+            .layers
+            .iter()
+            .flat_map(|l| l.w.data.iter().chain(&l.b.data).map(|v| v.to_bits()))
+            .collect::<Vec<u32>>()
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn ensemble_of_identical_models_matches_single() {
+    let build = || {
+        neurox::utils::set_seed(21);
+        Model::new(&[3, 5, 2], Activation::ReLU)
+    };
+    let x = Tensor::from_data(vec![0.2, -0.4, 1.0, 0.7, 0.1, -0.3], 2, 3);
+    let single = build().predict_proba(&x).expect("predict failed");
+
+    let mut models = vec![build(), build()];
+    let avg = neurox::model::ensemble_predict_proba(&mut models, &x).expect("ensemble failed");
+    for (a, b) in avg.data.iter().zip(&single.data) {
+        assert!((a - b).abs() < 1e-6);
+    }
+
+    models.push(Model::new(&[3, 5, 4], Activation::ReLU));
+    assert!(neurox::model::ensemble_predict_proba(&mut models, &x).is_err());
+    assert!(neurox::model::ensemble_predict_proba(&mut [], &x).is_err());
+}
+
+#[test]
+fn prune_neurons_shrinks_hidden_layer() {
+    let mut model = Model::new(&[3, 4, 2], Activation::ReLU);
+    let w1 = model.layers[1].w.clone();
+    model.prune_neurons(0, &[0, 2, 3]).expect("prune failed");
+
+    assert_eq!(model.layers[0].w.shape(), (3, 3));
+    assert_eq!(model.layers[0].b.shape(), (1, 3));
+    assert_eq!(model.layers[1].w.shape(), (3, 2));
+    assert_eq!(model.layers[1].w.get(1, 0), w1.get(2, 0));
+    let params: usize = model.layers.iter().map(|l| l.num_params()).sum();
+    assert_eq!(params, 3 * 3 + 3 + 3 * 2 + 2);
+
+    let out = model
+        .forward(&Tensor::random(5, 3))
+        .expect("forward failed");
+    assert_eq!(out.shape(), (5, 2));
+
+    assert!(model.prune_neurons(1, &[0]).is_err());
+    assert!(model.prune_neurons(0, &[0, 0]).is_err());
+    assert!(model.prune_neurons(0, &[3]).is_err());
+    assert!(model.prune_neurons(0, &[]).is_err());
+}
+
+#[test]
+fn gradient_snapshot_per_layer() {
+    let mut model = Model::new(&[3, 4, 2], Activation::Tanh);
+    assert!(
+        model
+            .gradients()
+            .iter()
+            .all(|(w, b)| w.is_none() && b.is_none())
+    );
+
+    let x = Tensor::random(5, 3);
+    let out = model.forward(&x).expect("forward failed");
+    model.backward(&out).expect("backward failed");
+
+    let grads = model.gradients();
+    assert_eq!(grads.len(), 2);
+    let (gw, gb) = &grads[0];
+    assert_eq!(gw.as_ref().unwrap().shape(), (3, 4));
+    assert_eq!(gb.as_ref().unwrap().shape(), (1, 4));
+
+    let slice = model.layers[0].grad_slice(1..3).expect("slice missing");
+    assert_eq!(slice.shape(), (2, 4));
+    assert_eq!(slice.data, gw.as_ref().unwrap().data[4..12].to_vec());
+    assert!(model.layers[0].grad_slice(2..5).is_none());
+}
+
+#[test]
+fn l1_penalty_sparsifies_weights() {
+    use neurox::SGD;
+    use neurox::model::TrainConfig;
+
+    let (x, y) = neurox::data::make_classification(40, 6, 2, 3);
+    let near_zero = |m: &Model| -> usize {
+        m.layers
+            .iter()
+            .flat_map(|l| &l.w.data)
+            .filter(|w| w.abs() < 1e-2)
+            .count()
+    };
+    let run = |l1: f32| -> (Model, Vec<f32>) {
+        neurox::utils::set_seed(13);
+        let mut model = Model::new(&[6, 8, 2], Activation::Tanh);
+        let mut config = TrainConfig::new(60, 8, 0.05);
+        config.l1 = l1;
+        let history = model
+            .train(&x, &y, &config, &mut SGD::new(0.05))
+            .expect("training failed");
+        (model, history)
+    };
+    let (plain, _) = run(0.0);
+    let (sparse, history) = run(0.01);
+    assert!(near_zero(&sparse) > near_zero(&plain));
+    let penalty: f32 = sparse
+        .layers
+        .iter()
+        .flat_map(|l| &l.w.data)
+        .map(|w| 0.01 * w.abs())
+        .sum();
+    assert!(*history.last().unwrap() >= penalty);
+}
+
+#[test]
+fn output_activation_applies_only_to_predict_proba() {
+    use neurox::model::OutputActivation;
+
+    neurox::utils::set_seed(17);
+    let mut model = Model::new(&[3, 4, 3], Activation::ReLU);
+    assert_eq!(model.output_activation, OutputActivation::Softmax);
+    let x = Tensor::from_data(vec![0.5, -1.0, 2.0, 3.0, 0.2, -0.7], 2, 3);
+
+    let probs = model.predict_proba(&x).expect("predict failed");
+    assert!(probs.assert_probability_rows(1e-5).is_ok());
+    let logits = model.forward(&x).expect("forward failed");
+    assert!(logits.assert_probability_rows(1e-5).is_err());
+
+    model.output_activation = OutputActivation::None;
+    assert_eq!(model.predict_proba(&x).unwrap().data, logits.data);
+    model.output_activation = OutputActivation::Sigmoid;
+    let sig = model.predict_proba(&x).unwrap();
+    assert!((sig.data[0] - 1.0 / (1.0 + (-logits.data[0]).exp())).abs() < 1e-6);
+}
+
+#[test]
+fn dead_units_finds_silenced_neuron() {
+    neurox::utils::set_seed(2);
+    let mut model = Model::new(&[3, 4, 2], Activation::ReLU);
+    // Unit 2 gets a large negative bias and no weights, so it never fires.
+    for r in 0..3 {
+        model.layers[0].w.set(r, 2, 0.0);
+    }
+    model.layers[0].b.set(0, 2, -1.0);
+
+    let x = Tensor::random(16, 3);
+    let dead = model.dead_units(&x).expect("dead_units failed");
+    assert_eq!(dead.len(), 2);
+    assert!(dead[0].contains(&2));
+    assert!(dead[1].is_empty());
+
+    // no pre-activation is cached under a tiny budget; the result must not change
+    model.set_checkpoint_memory_budget(1);
+    model.forward(&x).unwrap();
+    assert!(model.layers[0].preact().is_none());
+    assert_eq!(model.dead_units(&x).unwrap(), dead);
+}
+
+#[test]
+fn extract_features_returns_hidden_output() {
+    let mut model = Model::new(&[3, 4, 2], Activation::ReLU);
+    let x = Tensor::random(5, 3);
+    let features = model.extract_features(&x, 0).expect("extract failed");
+    assert_eq!(features.shape(), (5, 4));
+    assert!(features.data.iter().all(|&v| v >= 0.0));
+
+    let out = model.extract_features(&x, 1).expect("extract failed");
+    assert_eq!(out.data, model.forward(&x).unwrap().data);
+    assert!(model.extract_features(&x, 2).is_err());
+}
+
+#[test]
+fn reliability_curve_of_calibrated_predictions() {
+    // 10 rows at confidence 0.8 (8 correct) and 10 rows at 0.7 (7 correct).
+    let mut probs = Vec::new();
+    let mut targets = Vec::new();
+    for i in 0..10 {
+        probs.extend([0.8, 0.2]);
+        targets.extend(if i < 8 { [1.0, 0.0] } else { [0.0, 1.0] });
+    }
+    for i in 0..10 {
+        probs.extend([0.3, 0.7]);
+        targets.extend(if i < 7 { [0.0, 1.0] } else { [1.0, 0.0] });
+    }
+    let probs = Tensor::from_data(probs, 20, 2);
+    let targets = Tensor::from_data(targets, 20, 2);
+
+    let curve = neurox::model::reliability_curve(&probs, &targets, 5).expect("curve failed");
+    assert_eq!(curve.len(), 5);
+    assert_eq!(curve[0], (0.0, 0.0, 0));
+    let (conf, acc, count) = curve[4];
+    assert_eq!(count, 10);
+    assert!((conf - 0.8).abs() < 1e-6 && (acc - 0.8).abs() < 1e-6);
+    let (conf, acc, count) = curve[3];
+    assert_eq!(count, 10);
+    assert!((conf - 0.7).abs() < 1e-6 && (acc - 0.7).abs() < 1e-6);
+
+    assert!(neurox::model::reliability_curve(&probs, &targets, 0).is_err());
+}
+
+#[test]
+fn overconfidence_raises_ece() {
+    // Class 0 is right for 7 of 10 rows.
+    let targets: Vec<f32> = (0..10)
+        .flat_map(|i| if i < 7 { [1.0, 0.0] } else { [0.0, 1.0] })
+        .collect();
+    let targets = Tensor::from_data(targets, 10, 2);
+    let calibrated = Tensor::from_data([0.7, 0.3].repeat(10), 10, 2);
+    let overconfident = Tensor::from_data([0.99, 0.01].repeat(10), 10, 2);
+
+    let good = neurox::model::expected_calibration_error(&calibrated, &targets, 10).unwrap();
+    let bad = neurox::model::expected_calibration_error(&overconfident, &targets, 10).unwrap();
+    assert!(good < 1e-5);
+    assert!((bad - 0.29).abs() < 1e-5);
+}
+
+#[test]
+fn suggest_batch_size_scales_with_budget() {
+    let model = Model::new(&[10, 20, 5], Activation::ReLU);
+    // Per sample: 2 * (10 + 2 * 20 + 2 * 5) floats * 4 bytes = 480 bytes.
+    assert_eq!(model.suggest_batch_size(10, 100), 1);
+    assert_eq!(model.suggest_batch_size(10, 480 * 5), 4);
+    let large = model.suggest_batch_size(10, 1 << 30);
+    assert!(large.is_power_of_two() && large >= 1 << 20);
+}
+
+#[test]
+fn fgsm_increases_loss() {
+    neurox::utils::set_seed(11);
+    let x = Tensor::from_data(vec![1.0, 0.2, 0.1, 0.9, 0.8, 0.3, 0.2, 1.1], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0], 4, 2);
+    let mut model = Model::new(&[2, 8, 2], Activation::Tanh);
+    model
+        .train_adam(&x, &y, 100, 4, 0.05)
+        .expect("training failed");
+
+    let adv = model.fgsm(&x, &y, 0.3).expect("fgsm failed");
+    assert_eq!(adv.shape(), x.shape());
+    for (a, c) in adv.data.iter().zip(&x.data) {
+        assert!(((a - c).abs() - 0.3).abs() < 1e-6 || a == c);
+    }
+
+    let clean: f32 = model.per_sample_loss(&x, &y).unwrap().iter().sum();
+    let attacked: f32 = model.per_sample_loss(&adv, &y).unwrap().iter().sum();
+    assert!(attacked > clean, "{} <= {}", attacked, clean);
+}
+
+#[test]
+fn loss_scale_is_undone_before_step() {
+    use neurox::model::TrainConfig;
+
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let train = |scale: f32| {
+        neurox::utils::set_seed(5);
+        let mut model = Model::new(&[2, 6, 2], Activation::Tanh);
+        let mut config = TrainConfig::new(3, 2, 0.1);
+        config.loss_scale = scale;
+        model
+            .train(&x, &y, &config, &mut neurox::SGD::new(0.1))
+            .expect("training failed");
+        model
+    };
+
+    let plain = train(1.0);
+    let scaled = train(1000.0);
+    for (a, b) in plain.layers.iter().zip(&scaled.layers) {
+        for (p, q) in a.w.data.iter().zip(&b.w.data) {
+            assert!((p - q).abs() < 1e-5);
+        }
+        for (p, q) in a.b.data.iter().zip(&b.b.data) {
+            assert!((p - q).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn partial_fit_streams_xor_batches() {
+    neurox::utils::set_seed(3);
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let mut model = Model::new(&[2, 8, 2], Activation::Tanh);
+    let mut adam = neurox::Adam::new(0.05, &model.layers);
+
+    let losses: Vec<f32> = (0..300)
+        .map(|_| model.partial_fit(&x, &y, &mut adam).expect("step failed"))
+        .collect();
+    assert!(losses[299] < 0.5 * losses[0]);
+    assert!(losses[299] < losses[150]);
+
+    let bad = Tensor::zeros(4, 3);
+    assert!(model.partial_fit(&x, &bad, &mut adam).is_err());
+}
+
+#[test]
+fn compare_outputs_after_csv_round_trip() {
+    use neurox::model::compare_outputs;
+
+    neurox::utils::set_seed(8);
+    let mut original = Model::new(&[3, 4, 2], Activation::Tanh);
+    let mut reloaded = Model::new(&[3, 4, 2], Activation::Tanh);
+    let dir = std::env::temp_dir();
+    for (i, (src, dst)) in original
+        .layers
+        .iter()
+        .zip(reloaded.layers.iter_mut())
+        .enumerate()
+    {
+        let w_path = dir.join(format!("neurox_cmp_{}_w{}.csv", std::process::id(), i));
+        let b_path = dir.join(format!("neurox_cmp_{}_b{}.csv", std::process::id(), i));
+        src.w.to_csv(w_path.to_str().unwrap()).unwrap();
+        src.b.to_csv(b_path.to_str().unwrap()).unwrap();
+        dst.w = neurox::data::tensor_from_csv(w_path.to_str().unwrap()).unwrap();
+        dst.b = neurox::data::tensor_from_csv(b_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(w_path).unwrap();
+        std::fs::remove_file(b_path).unwrap();
+    }
+
+    let x = Tensor::from_data(vec![0.2, -0.4, 1.0, 0.7, 0.1, -0.3], 2, 3);
+    assert!(compare_outputs(&mut original, &mut reloaded, &x, 0.0).unwrap());
+
+    reloaded.layers[1].b.data[0] += 0.1;
+    assert!(!compare_outputs(&mut original, &mut reloaded, &x, 1e-3).unwrap());
+    assert!(compare_outputs(&mut original, &mut reloaded, &x, 0.2).unwrap());
+
+    let mut wider = Model::new(&[3, 4, 3], Activation::Tanh);
+    assert!(compare_outputs(&mut original, &mut wider, &x, 1.0).is_err());
+}
+
+#[test]
+fn compare_outputs_ignores_dropout() {
+    use neurox::layers::Dropout;
+    use neurox::model::compare_outputs;
+
+    let build = || {
+        neurox::utils::set_seed(31);
+        let mut model = Model::new(&[3, 32, 2], Activation::ReLU);
+        model.layers[0].dropout = Some(Dropout::new(0.5));
+        model
+    };
+    let (mut a, mut b) = (build(), build());
+    let x = Tensor::from_data(vec![0.2, -0.4, 1.0, 0.7, 0.1, -0.3], 2, 3);
+    for _ in 0..5 {
+        assert!(compare_outputs(&mut a, &mut b, &x, 0.0).unwrap());
+    }
+    assert!(a.layers[0].dropout.as_ref().unwrap().training);
+}
+
+#[test]
+fn roc_auc_of_separable_and_tied_scores() {
+    use neurox::model::{roc_auc, roc_auc_ovr};
+
+    let scores = [0.1, 0.35, 0.4, 0.8, 0.9];
+    assert_eq!(roc_auc(&scores, &[0, 0, 0, 1, 1]).unwrap(), 1.0);
+    assert_eq!(roc_auc(&scores, &[1, 1, 0, 0, 0]).unwrap(), 0.0);
+    // Three of the six positive/negative pairs are ordered correctly.
+    assert_eq!(roc_auc(&scores, &[0, 1, 0, 1, 0]).unwrap(), 0.5);
+    assert_eq!(roc_auc(&[0.5, 0.5], &[0, 1]).unwrap(), 0.5);
+
+    assert!(roc_auc(&scores, &[1, 1, 1, 1, 1]).is_err());
+    assert!(roc_auc(&scores, &[0, 1, 2, 0, 1]).is_err());
+    assert!(roc_auc(&scores, &[0, 1]).is_err());
+
+    let probs = Tensor::from_data(vec![0.7, 0.2, 0.1, 0.1, 0.8, 0.1, 0.2, 0.2, 0.6], 3, 3);
+    let targets = Tensor::from_data(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 3, 3);
+    assert_eq!(roc_auc_ovr(&probs, &targets).unwrap(), vec![1.0, 1.0, 1.0]);
+    let missing = Tensor::from_data(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0], 3, 3);
+    assert!(roc_auc_ovr(&probs, &missing).is_err());
+}
+
+#[test]
+fn train_binary_separates_linear_data() {
+    use neurox::model::{OutputActivation, TrainConfig};
+
+    neurox::utils::set_seed(4);
+    let x = Tensor::random_uniform(40, 2, -1.0, 1.0);
+    let labels: Vec<f32> = (0..40)
+        .map(|i| {
+            if x.get(i, 0) + x.get(i, 1) > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let y = Tensor::from_data(labels.clone(), 40, 1);
+
+    let mut model = Model::new(&[2, 1], Activation::None);
+    model.output_activation = OutputActivation::Sigmoid;
+    let config = TrainConfig::new(200, 8, 0.5);
+    let history = model
+        .train_binary(&x, &y, &config, &mut neurox::SGD::new(0.5))
+        .expect("training failed");
+    assert!(history[199] < 0.5 * history[0]);
+
+    let probs = model.predict_proba(&x).unwrap();
+    let correct = probs
+        .data
+        .iter()
+        .zip(&labels)
+        .filter(|&(&p, &t)| (p > 0.5) == (t == 1.0))
+        .count();
+    assert!(correct >= 38, "{} / 40 correct", correct);
+
+    let mut wide = Model::new(&[2, 2], Activation::None);
+    assert!(
+        wide.train_binary(&x, &y, &config, &mut neurox::SGD::new(0.5))
+            .is_err()
+    );
+    let bad = Tensor::zeros(39, 1);
+    assert!(
+        model
+            .train_binary(&x, &bad, &config, &mut neurox::SGD::new(0.5))
+            .is_err()
+    );
+}
+
+#[test]
+fn checkpoint_budget_recomputes_with_same_gradients() {
+    let build = || {
+        neurox::utils::set_seed(13);
+        Model::new(&[4, 16, 16, 16, 2], Activation::Tanh)
+    };
+    let x = Tensor::from_data((0..32).map(|v| (v as f32 * 0.37).sin()).collect(), 8, 4);
+    let y = Tensor::from_data([1.0, 0.0].repeat(8), 8, 2);
+
+    let mut full = build();
+    let mut lean = build();
+    // The cached (8, 4) + 3 x (8, 16) layer inputs, plus room for one (8, 16)
+    // pre-activation and the (8, 2) logits.
+    let inputs = 8 * (4 + 16 * 3) * 4;
+    lean.set_checkpoint_memory_budget(inputs + 8 * 16 * 4 + 8 * 2 * 4);
+
+    let g_full = full.input_gradient(&x, &y).expect("full backward failed");
+    let g_lean = lean.input_gradient(&x, &y).expect("lean backward failed");
+    let recomputed: Vec<bool> = lean.layers.iter().map(|l| l.recompute_preact).collect();
+    assert_eq!(recomputed, vec![false, true, true, false]);
+    assert!(lean.layers[1].preact().is_none());
+    assert!(full.layers[1].preact().is_some());
+
+    assert_eq!(g_full, g_lean);
+    for (a, b) in full.layers.iter().zip(&lean.layers) {
+        assert_eq!(a.grad_w, b.grad_w);
+        assert_eq!(a.grad_b, b.grad_b);
+    }
+
+    // a budget covering only the inputs caches no pre-activation
+    lean.set_checkpoint_memory_budget(inputs);
+    lean.forward(&x).unwrap();
+    assert!(lean.layers.iter().all(|l| l.recompute_preact));
+
+    lean.set_checkpoint_memory_budget(usize::MAX);
+    lean.forward(&x).unwrap();
+    assert!(lean.layers.iter().all(|l| !l.recompute_preact));
+}
+
+#[test]
+fn chunked_prediction_matches_full_batch() {
+    neurox::utils::set_seed(17);
+    let mut model = Model::new(&[3, 6, 4], Activation::ReLU);
+    let x = Tensor::random(11, 3);
+    let full = model.predict_proba(&x).unwrap();
+    for chunk in [1, 4, 11, 32] {
+        assert_eq!(model.predict_proba_chunked(&x, chunk).unwrap(), full);
+    }
+    assert!(model.predict_proba_chunked(&x, 0).is_err());
+}
+
+#[test]
+fn linear_probe_learns_on_frozen_features() {
+    use neurox::model::TrainConfig;
+
+    let (x, y) = neurox::data::make_classification(90, 4, 3, 6);
+    neurox::utils::set_seed(6);
+    let mut model = Model::new(&[4, 12, 3], Activation::Tanh);
+    let frozen = model.layers[0].w.clone();
+    let features = model.extract_features(&x, 0).unwrap();
+
+    let probe_accuracy = |head: neurox::Dense| {
+        let mut probe = Model::new(&[12, 3], Activation::None);
+        probe.layers[0] = head;
+        probe.accuracy(&features, &y, None).unwrap()
+    };
+    let mut adam = neurox::Adam::new(0.05, &model.layers);
+    let untrained = model
+        .linear_probe(0, 3, &x, &y, &TrainConfig::new(0, 16, 0.05), &mut adam)
+        .unwrap();
+    let trained = model
+        .linear_probe(0, 3, &x, &y, &TrainConfig::new(100, 16, 0.05), &mut adam)
+        .unwrap();
+    assert_eq!(trained.w.shape(), (12, 3));
+    let (before, after) = (probe_accuracy(untrained), probe_accuracy(trained));
+    assert!(after > before && after > 0.8, "{} -> {}", before, after);
+    assert_eq!(model.layers[0].w, frozen);
+
+    let bad = Tensor::zeros(90, 2);
+    assert!(
+        model
+            .linear_probe(0, 3, &x, &bad, &TrainConfig::new(1, 16, 0.05), &mut adam)
+            .is_err()
+    );
+    assert!(
+        model
+            .linear_probe(2, 3, &x, &y, &TrainConfig::new(1, 16, 0.05), &mut adam)
+            .is_err()
+    );
+}
+
+#[test]
+fn new_seeded_is_stable_across_architecture_changes() {
+    let a = Model::new_seeded(&[4, 8, 3], Activation::ReLU, 99);
+    let b = Model::new_seeded(&[4, 8, 3], Activation::ReLU, 99);
+    for (la, lb) in a.layers.iter().zip(&b.layers) {
+        assert_eq!(la.w, lb.w);
+        assert_eq!(la.b, lb.b);
+    }
+    assert_ne!(
+        Model::new_seeded(&[4, 8, 3], Activation::ReLU, 100).layers[0].w,
+        a.layers[0].w
+    );
+
+    // Appending a layer keeps the existing layers' initial weights.
+    let deeper = Model::new_seeded(&[4, 8, 3, 2], Activation::ReLU, 99);
+    assert_eq!(deeper.layers[0].w, a.layers[0].w);
+    assert_eq!(deeper.layers[1].w, a.layers[1].w);
+
+    // The global RNG stream is not disturbed.
+    neurox::utils::set_seed(1);
+    let expected = Tensor::random(2, 2);
+    neurox::utils::set_seed(1);
+    let _ = Model::new_seeded(&[4, 8, 3], Activation::ReLU, 99);
+    assert_eq!(Tensor::random(2, 2), expected);
+}
+
+#[test]
+fn train_with_mse_loss_fits_regression() {
+    let (x, y) = neurox::data::make_regression(64, 3, 0.01, 12);
+    neurox::utils::set_seed(12);
+    let mut model = Model::new(&[3, 1], Activation::None);
+    let mut sgd = neurox::SGD::new(0.05);
+    let history = model
+        .train_with_loss(&x, &y, 100, 16, &mut sgd, neurox::loss::mse_loss)
+        .expect("training failed");
+    assert_eq!(history.len(), 100);
+    assert!(
+        history[99] < 0.01 * history[0],
+        "{} -> {}",
+        history[0],
+        history[99]
+    );
+
+    let (final_loss, _) = neurox::loss::mse_loss(&model.forward(&x).unwrap(), &y);
+    assert!((final_loss - history[99]).abs() < 0.05);
+}
+
+#[test]
+fn from_layers_wraps_hand_built_layers() {
+    use neurox::model::OutputActivation;
+
+    let w = Tensor::from_data(vec![1.0, -1.0, 2.0, 0.5], 2, 2);
+    let b = Tensor::from_data(vec![0.0, 1.0], 1, 2);
+    let layer = neurox::Dense::from_weights(w, b, Activation::None).unwrap();
+    let mut model = Model::from_layers(vec![layer]);
+    assert_eq!(model.output_activation, OutputActivation::Softmax);
+
+    let x = Tensor::from_data(vec![1.0, 1.0], 1, 2);
+    assert_eq!(model.forward(&x).unwrap().data, vec![3.0, 0.5]);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "onnx")]
+
+use neurox::Model;
+use neurox::layers::Activation;
+
+/// Decodes one protobuf message into `(field, wire_type, payload)` entries, failing on
+/// malformed input. Varint payloads are returned as their little-endian bytes.
+fn parse(mut buf: &[u8]) -> Result<Vec<(u64, u64, Vec<u8>)>, String> {
+    fn varint(buf: &mut &[u8]) -> Result<u64, String> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&b, rest) = buf.split_first().ok_or("truncated varint")?;
+            *buf = rest;
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err("varint too long".into())
+    }
+
+    let mut fields = Vec::new();
+    while !buf.is_empty() {
+        let key = varint(&mut buf)?;
+        let (field, wire) = (key >> 3, key & 7);
+        let payload = match wire {
+            0 => varint(&mut buf)?.to_le_bytes().to_vec(),
+            2 => {
+                let len = varint(&mut buf)? as usize;
+                if len > buf.len() {
+                    return Err("truncated field".into());
+                }
+                let (head, rest) = buf.split_at(len);
+                buf = rest;
+                head.to_vec()
+            }
+            other => return Err(format!("unexpected wire type {}", other)),
+        };
+        fields.push((field, wire, payload));
+    }
+    Ok(fields)
+}
+
+#[test]
+fn export_onnx_writes_gemm_graph() {
+    let model = Model::new(&[3, 4, 2], Activation::ReLU);
+    let path = std::env::temp_dir().join(format!("neurox_export_{}.onnx", std::process::id()));
+    let path = path.to_str().unwrap();
+    model.export_onnx(path).expect("export failed");
+    let bytes = std::fs::read(path).expect("read failed");
+    std::fs::remove_file(path).ok();
+
+    let top = parse(&bytes).expect("model is not valid protobuf");
+    let graph = top
+        .iter()
+        .find(|(f, w, _)| *f == 7 && *w == 2)
+        .expect("missing graph");
+    let graph = parse(&graph.2).expect("graph is not valid protobuf");
+
+    let nodes: Vec<_> = graph.iter().filter(|(f, _, _)| *f == 1).collect();
+    let ops: Vec<String> = nodes
+        .iter()
+        .map(|n| {
+            let fields = parse(&n.2).expect("node is not valid protobuf");
+            let op = fields
+                .iter()
+                .find(|(f, _, _)| *f == 4)
+                .expect("missing op_type");
+            String::from_utf8(op.2.clone()).unwrap()
+        })
+        .collect();
+    assert_eq!(ops, vec!["Gemm", "Relu", "Gemm"]);
+
+    let initializers = graph.iter().filter(|(f, _, _)| *f == 5).count();
+    assert_eq!(initializers, 4);
+}
@@ -0,0 +1,206 @@
+use neurox::Tensor;
+use neurox::errors::NeuroxError;
+use neurox::ops;
+
+#[test]
+fn broadcast_binary_shapes() {
+    let m = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+
+    // scalar x matrix
+    let scalar = Tensor::from_data(vec![2.0], 1, 1);
+    let out = ops::mul(&scalar, &m).expect("scalar mul failed");
+    assert_eq!(out.shape(), (2, 3));
+    assert_eq!(out.data, vec![2.0, 4.0, 6.0, 8.0, 10.0, 12.0]);
+
+    // row x matrix
+    let row = Tensor::from_data(vec![1.0, 0.0, -1.0], 1, 3);
+    let out = ops::sub(&m, &row).expect("row sub failed");
+    assert_eq!(out.data, vec![0.0, 2.0, 4.0, 3.0, 5.0, 7.0]);
+
+    // col x matrix
+    let col = Tensor::from_data(vec![1.0, 2.0], 2, 1);
+    let out = ops::div(&m, &col).expect("col div failed");
+    assert_eq!(out.data, vec![1.0, 2.0, 3.0, 2.0, 2.5, 3.0]);
+
+    // row x col expands to a full matrix
+    let out = ops::maximum(&row, &col).expect("outer max failed");
+    assert_eq!(out.shape(), (2, 3));
+    assert_eq!(out.data, vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+    let out = ops::minimum(&m, &scalar).expect("min failed");
+    assert_eq!(out.data, vec![1.0, 2.0, 2.0, 2.0, 2.0, 2.0]);
+
+    // incompatible
+    let bad = Tensor::zeros(3, 2);
+    assert!(matches!(
+        ops::add(&m, &bad),
+        Err(NeuroxError::ShapeMismatch(_))
+    ));
+}
+
+#[test]
+fn stack_flatten_builds_batch() {
+    let a = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let b = Tensor::from_data(vec![5.0, 6.0, 7.0, 8.0], 2, 2);
+    let batch = ops::stack_flatten(&[a.clone(), b]).expect("stack failed");
+    assert_eq!(batch.shape(), (2, 4));
+    assert_eq!(batch.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+
+    assert!(ops::stack_flatten(&[a, Tensor::zeros(1, 4)]).is_err());
+    assert!(ops::stack_flatten(&[]).is_err());
+}
+
+#[test]
+fn max_pool1d_and_grad() {
+    let x = Tensor::from_data(vec![1.0, 3.0, 2.0, 4.0, 5.0], 1, 5);
+    let pooled = ops::max_pool1d(&x, 2, 2).expect("pool failed");
+    // trailing element 5.0 does not fill a window and is dropped
+    assert_eq!(pooled.data, vec![3.0, 4.0]);
+
+    let grad_out = Tensor::from_data(vec![10.0, 20.0], 1, 2);
+    let grad = ops::max_pool1d_grad(&x, &grad_out, 2, 2).expect("grad failed");
+    assert_eq!(grad.data, vec![0.0, 10.0, 0.0, 20.0, 0.0]);
+
+    assert!(ops::max_pool1d(&x, 0, 1).is_err());
+    assert!(ops::max_pool1d(&x, 6, 1).is_err());
+    assert!(ops::max_pool1d_grad(&x, &Tensor::zeros(1, 3), 2, 2).is_err());
+}
+
+#[test]
+fn lstsq_recovers_linear_coefficients() {
+    // y = 2 * x0 - 3 * x1 + 0.5 (bias column of ones)
+    let xs = [
+        (0.0, 1.0),
+        (1.0, 0.0),
+        (2.0, 1.0),
+        (3.0, 5.0),
+        (-1.0, 2.0),
+        (0.5, -0.5),
+    ];
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    for (x0, x1) in xs {
+        a.extend_from_slice(&[x0, x1, 1.0]);
+        b.push(2.0 * x0 - 3.0 * x1 + 0.5);
+    }
+    let a = Tensor::from_data(a, 6, 3);
+    let b = Tensor::from_data(b, 6, 1);
+
+    let coef = ops::lstsq(&a, &b).expect("lstsq failed");
+    assert_eq!(coef.shape(), (3, 1));
+    for (got, want) in coef.data.iter().zip([2.0, -3.0, 0.5]) {
+        assert!((got - want).abs() < 1e-3, "{} vs {}", got, want);
+    }
+
+    // duplicated column makes AᵀA singular
+    let collinear = Tensor::from_data(vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0], 3, 2);
+    let target = Tensor::from_data(vec![1.0, 2.0, 3.0], 3, 1);
+    assert!(matches!(
+        ops::lstsq(&collinear, &target),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn logsumexp_matches_naive_and_avoids_overflow() {
+    let x = Tensor::from_data(vec![0.5, -1.0, 2.0, 1.5, 0.0, -3.0], 2, 3);
+    let rows = ops::logsumexp(&x, 1).expect("lse failed");
+    assert_eq!(rows.shape(), (2, 1));
+    for i in 0..2 {
+        let naive = (0..3).map(|j| x.get(i, j).exp()).sum::<f32>().ln();
+        assert!((rows.get(i, 0) - naive).abs() < 1e-5);
+    }
+    let cols = ops::logsumexp(&x, 0).expect("lse failed");
+    assert_eq!(cols.shape(), (1, 3));
+    for j in 0..3 {
+        let naive = (0..2).map(|i| x.get(i, j).exp()).sum::<f32>().ln();
+        assert!((cols.get(0, j) - naive).abs() < 1e-5);
+    }
+
+    let big = Tensor::from_data(vec![1000.0, 1000.0], 1, 2);
+    let out = ops::logsumexp(&big, 1).expect("lse failed");
+    assert!(out.get(0, 0).is_finite());
+    assert!((out.get(0, 0) - (1000.0 + 2f32.ln())).abs() < 1e-3);
+
+    assert!(ops::logsumexp(&x, 2).is_err());
+}
+
+#[test]
+fn where_mask_checkerboard() {
+    let cond = Tensor::from_data(vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0], 2, 3);
+    let a = Tensor::from_data(vec![7.0; 6], 2, 3);
+    let b = Tensor::from_data(vec![-1.0; 6], 2, 3);
+    let out = ops::where_mask(&cond, &a, &b).expect("where_mask failed");
+    assert_eq!(out.data, vec![7.0, -1.0, 7.0, -1.0, 7.0, -1.0]);
+
+    let bad = ops::where_mask(&cond, &a, &Tensor::zeros(3, 2));
+    assert!(matches!(bad, Err(NeuroxError::ShapeMismatch(_))));
+}
+
+#[test]
+fn matmul_flags_matches_explicit_transposes() {
+    let a = Tensor::from_data(vec![1.0, -2.0, 3.0, 0.5, 4.0, -1.0], 2, 3);
+    let b = Tensor::from_data(vec![2.0, 1.0, 0.0, -3.0, 1.5, 2.5], 3, 2);
+    let at = a.transpose();
+    let bt = b.transpose();
+
+    let cases = [
+        (&a, &b, false, false, ops::matmul(&a, &b)),
+        (&at, &b, true, false, ops::matmul(&a, &b)),
+        (&a, &bt, false, true, ops::matmul(&a, &b)),
+        (&at, &bt, true, true, ops::matmul(&a, &b)),
+    ];
+    for (x, y, ta, tb, expected) in cases {
+        let got = ops::matmul_flags(x, y, ta, tb).expect("matmul_flags failed");
+        let expected = expected.unwrap();
+        assert_eq!(got.shape(), expected.shape());
+        assert_eq!(got.data, expected.data, "trans_a={} trans_b={}", ta, tb);
+    }
+
+    // (3, 2)ᵀ x (3, 2) is (2, 2); without the flag the inner dimensions clash.
+    assert_eq!(
+        ops::matmul_flags(&b, &b, true, false).unwrap().shape(),
+        (2, 2)
+    );
+    assert!(matches!(
+        ops::matmul_flags(&b, &b, false, false),
+        Err(NeuroxError::ShapeMismatch(_))
+    ));
+}
+
+#[test]
+fn vstack_concatenates_rows() {
+    let a = Tensor::from_data(vec![1.0, 2.0], 1, 2);
+    let b = Tensor::from_data(vec![3.0, 4.0, 5.0, 6.0], 2, 2);
+    let out = ops::vstack(&[a.clone(), b]).expect("vstack failed");
+    assert_eq!(
+        out,
+        Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3, 2)
+    );
+
+    assert!(ops::vstack(&[]).is_err());
+    assert!(ops::vstack(&[a, Tensor::zeros(1, 3)]).is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn fast_matmul_is_close_and_reproducible() {
+    let a = Tensor::from_data(
+        (0..6 * 100).map(|v| (v as f32 * 0.13).sin()).collect(),
+        6,
+        100,
+    );
+    let b = Tensor::from_data(
+        (0..100 * 5).map(|v| (v as f32 * 0.29).cos()).collect(),
+        100,
+        5,
+    );
+    let exact = ops::matmul(&a, &b).unwrap();
+
+    neurox::utils::set_deterministic(false);
+    let fast = ops::matmul(&a, &b).unwrap();
+    let again = ops::matmul(&a, &b).unwrap();
+    neurox::utils::set_deterministic(true);
+
+    assert_eq!(fast, again);
+    assert!(fast.approx_eq(&exact, 1e-4));
+}
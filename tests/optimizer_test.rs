@@ -0,0 +1,212 @@
+use neurox::layers::{Activation, Dense};
+use neurox::loss;
+use neurox::model::TrainConfig;
+use neurox::{Adam, Model, Tensor};
+
+#[test]
+fn adam_handles_layers_added_after_construction() {
+    let mut model = Model::new(&[2, 3], Activation::ReLU);
+    let mut adam = Adam::new(0.01, &model.layers);
+    model.layers.push(Dense::new(3, 2, Activation::None));
+
+    let x = Tensor::random(4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0], 4, 2);
+    let preds = model.forward(&x).expect("forward failed");
+    let (_loss, grad) = loss::mse_loss(&preds, &y);
+    model.backward(&grad).expect("backward failed");
+
+    let before = model.layers[1].w.data.clone();
+    adam.step(&mut model.layers);
+    assert_eq!(adam.m_w.len(), 2);
+    assert_eq!(adam.m_w[1].len(), 6);
+    assert_ne!(model.layers[1].w.data, before);
+}
+
+#[test]
+fn averaged_micro_batches_match_full_batch_moments() {
+    let x = Tensor::from_data(vec![0.1, 0.9, 0.4, 0.2, 0.7, 0.5, 0.3, 0.8], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0], 4, 2);
+    let build = || {
+        neurox::utils::set_seed(11);
+        Model::new(&[2, 3, 2], Activation::Tanh)
+    };
+
+    let mut full = build();
+    let preds = full.forward(&x).expect("forward failed");
+    let (_loss, grad) = loss::mse_loss(&preds, &y);
+    full.backward(&grad).expect("backward failed");
+    let mut adam_full = Adam::new(0.01, &full.layers);
+    adam_full.step(&mut full.layers);
+
+    let mut micro = build();
+    let mut sums: Vec<(Vec<f32>, Vec<f32>)> = micro
+        .layers
+        .iter()
+        .map(|l| (vec![0.0; l.w.data.len()], vec![0.0; l.b.data.len()]))
+        .collect();
+    for start in [0, 2] {
+        let xb = x.slice_rows(start, start + 2).unwrap();
+        let yb = y.slice_rows(start, start + 2).unwrap();
+        let preds = micro.forward(&xb).expect("forward failed");
+        let (_loss, grad) = loss::mse_loss(&preds, &yb);
+        micro.backward(&grad).expect("backward failed");
+        for (l, (sw, sb)) in micro.layers.iter().zip(sums.iter_mut()) {
+            let gw = l.grad_w.as_ref().unwrap();
+            let gb = l.grad_b.as_ref().unwrap();
+            sw.iter_mut().zip(&gw.data).for_each(|(s, g)| *s += g);
+            sb.iter_mut().zip(&gb.data).for_each(|(s, g)| *s += g);
+        }
+    }
+    for (l, (sw, sb)) in micro.layers.iter_mut().zip(sums) {
+        let (wr, wc) = l.w.shape();
+        let (br, bc) = l.b.shape();
+        l.grad_w = Some(Tensor::from_data(sw, wr, wc).map(|g| g / 2.0));
+        l.grad_b = Some(Tensor::from_data(sb, br, bc).map(|g| g / 2.0));
+    }
+    let mut adam_micro = Adam::new(0.01, &micro.layers);
+    adam_micro.step(&mut micro.layers);
+
+    assert_eq!(adam_micro.t, adam_full.t);
+    let close = |a: &[Vec<f32>], b: &[Vec<f32>]| {
+        a.iter()
+            .flatten()
+            .zip(b.iter().flatten())
+            .all(|(p, q)| (p - q).abs() <= 1e-6 * p.abs().max(1e-6))
+    };
+    assert!(close(&adam_micro.m_w, &adam_full.m_w));
+    assert!(close(&adam_micro.v_w, &adam_full.v_w));
+    assert!(close(&adam_micro.m_b, &adam_full.m_b));
+    assert!(close(&adam_micro.v_b, &adam_full.v_b));
+}
+
+#[test]
+fn adam_state_round_trip_resumes_exactly() {
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let build = || {
+        neurox::utils::set_seed(5);
+        Model::new(&[2, 4, 2], Activation::Tanh)
+    };
+
+    let mut model = build();
+    let mut adam = Adam::new(0.05, &model.layers);
+    model
+        .train(&x, &y, &TrainConfig::new(3, 2, 0.05), &mut adam)
+        .expect("training failed");
+
+    let path = std::env::temp_dir().join(format!("neurox_adam_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    adam.save_state(path).expect("save failed");
+    let mut resumed = build();
+    for (dst, src) in resumed.layers.iter_mut().zip(&model.layers) {
+        dst.w.data.copy_from_slice(&src.w.data);
+        dst.b.data.copy_from_slice(&src.b.data);
+    }
+    let mut loaded = Adam::load_state(path).expect("load failed");
+    std::fs::remove_file(path).ok();
+    assert_eq!(loaded.t, adam.t);
+
+    let config = TrainConfig::new(2, 2, 0.05);
+    model.train(&x, &y, &config, &mut adam).unwrap();
+    resumed.train(&x, &y, &config, &mut loaded).unwrap();
+    for (a, b) in model.layers.iter().zip(&resumed.layers) {
+        assert_eq!(a.w.data, b.w.data);
+        assert_eq!(a.b.data, b.b.data);
+    }
+    assert_eq!(loaded.m_w, adam.m_w);
+    assert_eq!(loaded.v_b, adam.v_b);
+}
+
+#[test]
+fn train_adam_continue_keeps_optimizer_state() {
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let mut model = Model::new(&[2, 4, 2], Activation::Tanh);
+    let mut adam = Adam::new(0.01, &model.layers);
+
+    model
+        .train_adam_continue(&mut adam, &x, &y, 3, 2)
+        .expect("training failed");
+    assert_eq!(adam.t, 6);
+    let m_after_first = adam.m_w.clone();
+    model
+        .train_adam_continue(&mut adam, &x, &y, 2, 4)
+        .expect("training failed");
+    assert_eq!(adam.t, 8);
+    assert_ne!(adam.m_w, m_after_first);
+}
+
+#[test]
+fn nesterov_converges_at_least_as_fast_as_momentum() {
+    use neurox::SGD;
+
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    let final_loss = |nesterov: bool| -> f32 {
+        neurox::utils::set_seed(6);
+        let mut model = Model::new(&[2, 8, 2], Activation::Tanh);
+        let mut sgd = SGD::with_momentum(0.1, 0.9, nesterov);
+        let history = model
+            .train(&x, &y, &TrainConfig::new(50, 4, 0.1), &mut sgd)
+            .expect("training failed");
+        *history.last().unwrap()
+    };
+    assert!(final_loss(true) <= final_loss(false));
+}
+
+#[test]
+fn adagrad_step_size_shrinks_under_constant_gradient() {
+    let mut layers = vec![Dense::new(2, 1, Activation::None)];
+    let mut opt = neurox::AdaGrad::new(0.1, &layers);
+    let mut steps = Vec::new();
+    for _ in 0..4 {
+        layers[0].grad_w = Some(Tensor::from_data(vec![5.0, 0.01], 2, 1));
+        layers[0].grad_b = Some(Tensor::zeros(1, 1));
+        let before = layers[0].w.data.clone();
+        opt.step(&mut layers);
+        steps.push((
+            before[0] - layers[0].w.data[0],
+            before[1] - layers[0].w.data[1],
+        ));
+    }
+    // Step k moves by lr / sqrt(k) regardless of the gradient magnitude.
+    for (k, &(big, small)) in steps.iter().enumerate() {
+        let expected = 0.1 / ((k + 1) as f32).sqrt();
+        assert!((big - expected).abs() < 1e-5);
+        assert!((small - expected).abs() < 1e-4);
+    }
+    assert!((opt.g2_w[0][0] - 100.0).abs() < 1e-3);
+
+    let x = Tensor::from_data(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], 4, 2);
+    let y = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0], 4, 2);
+    neurox::utils::set_seed(2);
+    let mut model = Model::new(&[2, 8, 2], Activation::Tanh);
+    let before = model.per_sample_loss(&x, &y).unwrap().iter().sum::<f32>();
+    model
+        .train_adagrad(&x, &y, 200, 4, 0.1)
+        .expect("training failed");
+    let after = model.per_sample_loss(&x, &y).unwrap().iter().sum::<f32>();
+    assert!(after < before);
+}
+
+#[test]
+fn one_cycle_rises_then_anneals() {
+    use neurox::optimizer::{LrSchedule, OneCycle};
+
+    let sched = OneCycle::new(101, 0.1, 0.3);
+    assert!((sched.lr_at(0) - 0.004).abs() < 1e-7);
+    assert!((sched.lr_at(15) - 0.052).abs() < 1e-6);
+    assert!((sched.lr_at(30) - 0.1).abs() < 1e-7);
+    assert!(sched.lr_at(29) < sched.lr_at(30) && sched.lr_at(31) < sched.lr_at(30));
+    assert!(sched.lr_at(60) < sched.lr_at(40));
+    assert!((sched.lr_at(100) - 4e-7).abs() < 1e-9);
+    assert_eq!(sched.lr_at(500), sched.lr_at(100));
+
+    assert!((sched.momentum_at(0) - 0.95).abs() < 1e-6);
+    assert!((sched.momentum_at(30) - 0.85).abs() < 1e-6);
+    assert!((sched.momentum_at(100) - 0.95).abs() < 1e-6);
+
+    let mut config = TrainConfig::new(10, 4, 0.1);
+    config.schedule = Some(Box::new(OneCycle::new(10, 0.1, 0.5)));
+    assert!((config.lr_at(5) - 0.1).abs() < 1e-3);
+}
@@ -0,0 +1,311 @@
+use neurox::Tensor;
+use neurox::errors::NeuroxError;
+
+#[test]
+fn mul_row_and_col_broadcast() {
+    let t = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+
+    let rows = t
+        .mul_row_broadcast(&Tensor::from_data(vec![2.0, 3.0], 1, 2))
+        .expect("row broadcast failed");
+    assert_eq!(rows.data, vec![2.0, 6.0, 6.0, 12.0]);
+
+    let cols = t
+        .mul_col_broadcast(&Tensor::from_data(vec![2.0, 3.0], 2, 1))
+        .expect("col broadcast failed");
+    assert_eq!(cols.data, vec![2.0, 4.0, 9.0, 12.0]);
+
+    let bad = t.mul_row_broadcast(&Tensor::from_data(vec![1.0, 2.0, 3.0], 1, 3));
+    assert!(matches!(bad, Err(NeuroxError::ShapeMismatch(_))));
+    let bad = t.mul_col_broadcast(&Tensor::from_data(vec![1.0, 2.0], 1, 2));
+    assert!(matches!(bad, Err(NeuroxError::ShapeMismatch(_))));
+}
+
+#[test]
+fn diag_and_trace_of_identity() {
+    let eye = Tensor::from_data(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 3, 3);
+    let d = eye.diag().expect("diag failed");
+    assert_eq!(d.shape(), (3, 1));
+    assert_eq!(d.data, vec![1.0, 1.0, 1.0]);
+    assert_eq!(eye.trace().expect("trace failed"), 3.0);
+
+    let rect = Tensor::zeros(2, 3);
+    assert!(matches!(rect.diag(), Err(NeuroxError::ShapeMismatch(_))));
+    assert!(rect.trace().is_err());
+}
+
+#[test]
+fn from_rows_validates_shape() {
+    let t = Tensor::from_rows(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]])
+        .expect("from_rows failed");
+    assert_eq!(t.shape(), (2, 3));
+    assert_eq!(t.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let jagged = Tensor::from_rows(vec![vec![1.0, 2.0], vec![3.0]]);
+    assert!(matches!(jagged, Err(NeuroxError::InvalidArgument(_))));
+}
+
+#[test]
+fn slice_rows_checks_bounds() {
+    let t = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3, 2);
+    let mid = t.slice_rows(1, 3).expect("slice failed");
+    assert_eq!(mid.shape(), (2, 2));
+    assert_eq!(mid.data, vec![3.0, 4.0, 5.0, 6.0]);
+
+    let empty = t.slice_rows(2, 2).expect("empty slice failed");
+    assert_eq!(empty.shape(), (0, 2));
+
+    assert!(matches!(
+        t.slice_rows(1, 4),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        t.slice_rows(2, 1),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn sub_row_broadcast_centers_rows() {
+    let t = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let out = t
+        .sub_row_broadcast(&Tensor::from_data(vec![1.0, 2.0], 1, 2))
+        .expect("sub failed");
+    assert_eq!(out.data, vec![0.0, 0.0, 2.0, 2.0]);
+
+    let bad = t.sub_row_broadcast(&Tensor::from_data(vec![1.0, 2.0], 2, 1));
+    assert!(matches!(bad, Err(NeuroxError::ShapeMismatch(_))));
+}
+
+#[test]
+fn split_at_row_pieces() {
+    let t = Tensor::from_data((0..8).map(|v| v as f32).collect(), 4, 2);
+    let (head, tail) = t.split_at_row(1).expect("split failed");
+    assert_eq!(head.shape(), (1, 2));
+    assert_eq!(tail.shape(), (3, 2));
+    assert_eq!(head.data, vec![0.0, 1.0]);
+    assert_eq!(tail.data, vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+
+    assert!(t.split_at_row(5).is_err());
+}
+
+#[test]
+fn probability_rows_check() {
+    let logits = Tensor::from_data(vec![1.0, 2.0, 3.0, -1.0, 0.0, 5.0], 2, 3);
+    let probs = neurox::activations::softmax(&logits);
+    assert!(probs.assert_probability_rows(1e-5).is_ok());
+
+    let unnormalized = Tensor::from_data(vec![0.5, 0.5, 0.5, 0.2, 0.3, 0.5], 2, 3);
+    assert!(matches!(
+        unnormalized.assert_probability_rows(1e-5),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+    let negative = Tensor::from_data(vec![1.5, -0.5], 1, 2);
+    assert!(negative.assert_probability_rows(1e-5).is_err());
+}
+
+#[test]
+fn map_rows_normalizes_rows() {
+    let t = Tensor::from_data(vec![1.0, 3.0, 2.0, 2.0, 0.0, 4.0], 2, 3);
+    let normalized = t
+        .map_rows(|row| {
+            let sum: f32 = row.iter().sum();
+            row.iter().map(|v| v / sum).collect()
+        })
+        .expect("map_rows failed");
+    assert_eq!(
+        normalized.data,
+        vec![1.0 / 6.0, 0.5, 1.0 / 3.0, 1.0 / 3.0, 0.0, 2.0 / 3.0]
+    );
+
+    let bad = t.map_rows(|row| row[..2].to_vec());
+    assert!(matches!(bad, Err(NeuroxError::ShapeMismatch(_))));
+}
+
+#[test]
+fn var_and_std_with_ddof() {
+    // Column [2, 4, 4, 4, 5, 5, 7, 9]: mean 5, squared deviations sum to 32.
+    let col = Tensor::from_data(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], 8, 1);
+    let pop = col.var_axis(0, 0).expect("var failed");
+    assert_eq!(pop.shape(), (1, 1));
+    assert!((pop.data[0] - 4.0).abs() < 1e-6);
+    let sample = col.var_axis(0, 1).expect("var failed");
+    assert!((sample.data[0] - 32.0 / 7.0).abs() < 1e-6);
+    assert!((col.std_axis(0, 0).unwrap().data[0] - 2.0).abs() < 1e-6);
+
+    // A large offset must not destroy precision.
+    let shifted = col.map(|v| v + 1.0e4).transpose();
+    let rows = shifted.var_axis(1, 0).expect("var failed");
+    assert_eq!(rows.shape(), (1, 1));
+    assert!((rows.data[0] - 4.0).abs() < 1e-3);
+
+    assert!(matches!(
+        col.var_axis(2, 0),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+    assert!(col.var_axis(1, 1).is_err());
+}
+
+#[test]
+fn spectral_norm_of_diagonal() {
+    let d = Tensor::from_data(vec![2.0, 0.0, 0.0, 0.0, -5.0, 0.0, 0.0, 0.0, 1.0], 3, 3);
+    assert!((d.spectral_norm(50) - 5.0).abs() < 1e-3);
+
+    let rect = Tensor::from_data(vec![3.0, 0.0, 0.0, 0.0, 1.0, 0.0], 2, 3);
+    assert!((rect.spectral_norm(50) - 3.0).abs() < 1e-3);
+    assert_eq!(Tensor::zeros(2, 2).spectral_norm(10), 0.0);
+}
+
+#[test]
+fn histogram_of_uniform_values() {
+    let t = Tensor::from_data((0..100).map(|v| v as f32 / 10.0).collect(), 10, 10);
+    let (edges, counts) = t.histogram(5, None).unwrap();
+    assert_eq!(edges.len(), 6);
+    assert_eq!(edges[0], 0.0);
+    assert!((edges[5] - 9.9).abs() < 1e-5);
+    assert_eq!(counts.iter().sum::<usize>(), 100);
+    assert!(counts.iter().all(|&c| (19..=21).contains(&c)));
+
+    let (_, clipped) = t.histogram(2, Some((0.0, 1.0))).unwrap();
+    assert_eq!(clipped, vec![5, 6]);
+
+    let (edges, counts) = Tensor::from_data(vec![3.0; 4], 2, 2)
+        .histogram(2, None)
+        .unwrap();
+    assert_eq!(edges, vec![2.5, 3.0, 3.5]);
+    assert_eq!(counts, vec![0, 4]);
+
+    for bad in [(1.0, 0.0), (0.0, f32::INFINITY), (f32::NAN, 1.0)] {
+        assert!(matches!(
+            t.histogram(2, Some(bad)),
+            Err(NeuroxError::InvalidArgument(_))
+        ));
+    }
+    assert!(t.histogram(0, None).is_err());
+}
+
+#[test]
+fn abs_and_signum_on_mixed_signs() {
+    let t = Tensor::from_data(vec![-2.5, 0.0, 3.0, -0.0], 2, 2);
+    assert_eq!(t.abs().data, vec![2.5, 0.0, 3.0, 0.0]);
+    assert_eq!(t.signum().data, vec![-1.0, 0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn topk_row_orders_descending() {
+    let t = Tensor::from_data(vec![0.0, 0.0, 0.0, 0.1, 0.7, 0.05, 0.7, 0.15], 2, 4);
+    let (idx, vals) = t.topk_row(1, 3).expect("topk failed");
+    assert_eq!(idx, vec![0, 2, 3]);
+    assert_eq!(vals, vec![0.7, 0.7, 0.15]);
+
+    assert_eq!(t.topk_row(0, 0).unwrap().0, Vec::<usize>::new());
+    assert!(matches!(
+        t.topk_row(2, 1),
+        Err(NeuroxError::InvalidArgument(_))
+    ));
+    assert!(t.topk_row(0, 5).is_err());
+}
+
+#[test]
+fn perturbed_changes_only_target() {
+    let t = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let p = t.perturbed(2, 0.5);
+    assert_eq!(p.data, vec![1.0, 2.0, 3.5, 4.0]);
+    assert_eq!(p.shape(), (2, 2));
+    assert_eq!(t.data, vec![1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn round_to_and_f64_conversion() {
+    let t = Tensor::from_data(vec![0.123456, -3.45678, 1.0006, 7.0], 2, 2);
+    let r = t.round_to(3);
+    assert_eq!(r.data, vec![0.123, -3.457, 1.001, 7.0]);
+
+    let (rows, cols, wide) = t.to_f64();
+    assert_eq!((rows, cols), (2, 2));
+    assert_eq!(wide[0], 0.123456f32 as f64);
+    assert_eq!(Tensor::from_f64((rows, cols, wide)), t);
+
+    let column = Tensor::from_data(vec![1.5, -2.0, 0.25], 3, 1);
+    assert_eq!(Tensor::from_f64(column.to_f64()), column);
+}
+
+#[test]
+fn equality_compares_shape_and_data() {
+    let a = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    let b = Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+    assert_eq!(a, b);
+    assert_ne!(a, Tensor::from_data(vec![1.0, 2.0, 3.0, 4.0], 1, 4));
+    assert_ne!(a, Tensor::from_data(vec![1.0, 2.0, 3.0, 4.5], 2, 2));
+
+    let nudged = a.map(|v| v + 1e-6);
+    assert_ne!(a, nudged);
+    assert!(a.approx_eq(&nudged, 1e-5));
+    assert!(!a.approx_eq(&nudged, 1e-7));
+    assert!(!a.approx_eq(&a.transpose().slice_rows(0, 1).unwrap(), 1.0));
+}
+
+#[test]
+fn zeros_like_and_ones_like_match_shape() {
+    let t = Tensor::from_data(vec![1.0, -2.0, 3.0, 4.0, 5.0, 6.0], 3, 2);
+    let z = t.zeros_like();
+    assert_eq!(z.shape(), (3, 2));
+    assert!(z.data.iter().all(|&v| v == 0.0));
+    let o = t.ones_like();
+    assert_eq!(o, Tensor::from_data(vec![1.0; 6], 3, 2));
+    assert_eq!(Tensor::zeros(0, 4).ones_like().shape(), (0, 4));
+}
+
+#[test]
+fn normalize_rows_and_cols_to_unit_norm() {
+    let t = Tensor::from_data(vec![3.0, 4.0, 0.0, 0.0, -1.0, 1.0], 3, 2);
+    let rows = t.normalize_rows();
+    assert!(rows.approx_eq(
+        &Tensor::from_data(
+            vec![0.6, 0.8, 0.0, 0.0, -0.5f32.sqrt(), 0.5f32.sqrt()],
+            3,
+            2
+        ),
+        1e-6
+    ));
+    assert!(rows.data.iter().all(|v| v.is_finite()));
+
+    let cols = t.normalize_cols();
+    for j in 0..2 {
+        let norm: f32 = (0..3).map(|i| cols.get(i, j).powi(2)).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+    }
+    let zero_col = Tensor::from_data(vec![0.0, 2.0, 0.0, 2.0], 2, 2).normalize_cols();
+    assert_eq!(zero_col.data, vec![0.0, 0.5f32.sqrt(), 0.0, 0.5f32.sqrt()]);
+}
+
+#[test]
+fn row_entropy_of_uniform_and_one_hot() {
+    let p = Tensor::from_data(
+        vec![
+            0.25, 0.25, 0.25, 0.25, 0.0, 1.0, 0.0, 0.0, 0.5, 0.5, 0.0, 0.0,
+        ],
+        3,
+        4,
+    );
+    let h = p.row_entropy();
+    assert_eq!(h.shape(), (3, 1));
+    assert!((h.data[0] - 4f32.ln()).abs() < 1e-6);
+    assert_eq!(h.data[1], 0.0);
+    assert!((h.data[2] - 2f32.ln()).abs() < 1e-6);
+}
+
+#[test]
+fn condition_number_of_diagonal() {
+    let d = Tensor::from_data(vec![2.0, 0.0, 0.0, 0.0, -5.0, 0.0, 0.0, 0.0, 0.5], 3, 3);
+    assert!((d.condition_number(200) - 10.0).abs() < 1e-2);
+
+    let wide = Tensor::from_data(vec![4.0, 0.0, 0.0, 0.0, 1.0, 0.0], 2, 3);
+    assert!((wide.condition_number(100) - 4.0).abs() < 1e-3);
+
+    let eye = Tensor::from_data(vec![1.0, 0.0, 0.0, 1.0], 2, 2);
+    assert!((eye.condition_number(10) - 1.0).abs() < 1e-6);
+    let singular = Tensor::from_data(vec![1.0, 2.0, 2.0, 4.0], 2, 2);
+    assert!(singular.condition_number(50) > 1e3);
+    assert_eq!(Tensor::zeros(2, 2).condition_number(10), f32::INFINITY);
+}
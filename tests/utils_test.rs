@@ -0,0 +1,49 @@
+use neurox::Tensor;
+use neurox::utils;
+
+#[test]
+fn rng_state_round_trip() {
+    utils::set_seed(7);
+    let _warmup = Tensor::random(3, 3);
+
+    let state = utils::rng_state();
+    let first = Tensor::random(2, 4);
+
+    utils::set_rng_state(&state).expect("restore failed");
+    let second = Tensor::random(2, 4);
+    assert_eq!(first.data, second.data);
+
+    assert!(utils::set_rng_state(&state[..10]).is_err());
+}
+
+#[test]
+fn loss_slope_of_linear_history() {
+    // Flat start, then falling by 0.25 per step; only the last 4 values are used.
+    let history = [5.0, 5.0, 5.0, 3.0, 2.75, 2.5, 2.25];
+    assert!((utils::loss_slope(&history, 4) + 0.25).abs() < 1e-6);
+    assert!(utils::loss_slope(&[1.0, 1.0, 1.0], 10).abs() < 1e-7);
+    assert_eq!(utils::loss_slope(&[1.0], 5), 0.0);
+}
+
+#[test]
+fn ema_meter_tracks_stream() {
+    let mut ema = utils::EmaMeter::new(0.9);
+    assert_eq!(ema.value(), 0.0);
+    for _ in 0..50 {
+        ema.update(2.0);
+    }
+    assert!((ema.value() - 2.0).abs() < 1e-6);
+
+    ema.update(12.0);
+    assert!((ema.value() - 3.0).abs() < 1e-5);
+    let mut last = ema.value();
+    for _ in 0..20 {
+        ema.update(12.0);
+        assert!(ema.value() > last && ema.value() < 12.0);
+        last = ema.value();
+    }
+    for _ in 0..200 {
+        ema.update(12.0);
+    }
+    assert!((ema.value() - 12.0).abs() < 1e-3);
+}